@@ -1,56 +1,496 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fmt::{self, Display, Write as _},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
 use macros::node;
 
 use crate::{
-    comment,
-    node::{formatter::NodeFormatter, types::number::NumberNode, Node},
-    util::DocStr,
+    comment, docstr_empty,
+    node::{
+        formatter::{
+            lint::{Lint, LintErrors, Severity},
+            options::FormatOptions,
+            NodeFormatter,
+        },
+        key::Key,
+        types::placeholder::Variables,
+        types::{comment::CommentNode, number::NumberNode},
+        Node,
+    },
+    util::{DocStr, Indent},
 };
 
 #[derive(Default)]
-pub struct Example(Vec<Node>);
+pub struct Example {
+    name: Option<String>,
+    description: Option<DocStr>,
+    nodes: Vec<Node>,
+    secrets: Vec<Key>,
+}
 
 impl Example {
+    pub const DEFAULT_NAME: &'static str = "example";
+
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::default().with_name(name)
+    }
+
     pub fn from_nodes(nodes: impl IntoIterator<Item = Node>) -> Self {
         Self::from_nodes_iter(nodes.into_iter())
     }
 
     pub fn from_nodes_iter(nodes: impl Iterator<Item = Node>) -> Self {
-        Self(nodes.collect())
+        Self {
+            nodes: nodes.collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_description(mut self, description: impl Into<DocStr>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_nodes(mut self, nodes: impl IntoIterator<Item = Node>) -> Self {
+        self.nodes.extend(nodes);
+        self
+    }
+
+    pub fn add_node(mut self, node: Node) -> Self {
+        self.nodes.push(node);
+        self
     }
 
-    pub fn add_comment(mut self, comment: impl Into<DocStr>) -> Self {
-        self.0.push(node!(comment!(comment)));
+    /// Replaces the value at `key` with a placeholder wherever it shows up, e.g. in nodes built
+    /// from real values, so that it never ends up in generated files.
+    pub fn with_secret(mut self, key: impl Into<Key>) -> Self {
+        self.secrets.push(key.into());
         self
     }
 
-    pub fn add_number(mut self, number: NumberNode) -> Self {
-        self.0.push(node!(number));
+    /// Substitutes placeholders, then redacts secrets, in case a variable is bound to one.
+    fn prepare(mut self, variables: &Variables) -> Self {
+        for node in &mut self.nodes {
+            node.substitute(variables);
+            node.redact(&self.secrets);
+        }
+
         self
     }
+
+    pub fn add_comment(self, comment: impl Into<DocStr>) -> Self {
+        self.add_node(node!(comment!(comment)))
+    }
+
+    pub fn add_number(self, number: NumberNode) -> Self {
+        self.add_node(node!(number))
+    }
 }
 
 pub trait NodeSchema {
     fn examples() -> impl IntoIterator<Item = Example>;
 }
 
-pub struct Generator;
+/// Why the examples of a schema couldn't be generated.
+#[derive(Debug)]
+pub enum GenerateError {
+    /// Several examples would get the same file name.
+    DuplicateName(String),
+    Lint(LintErrors),
+}
+
+impl Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateName(name) => write!(f, "Several examples are named {name:?}"),
+            Self::Lint(errors) => errors.fmt(f),
+        }
+    }
+}
+
+impl Error for GenerateError {}
+
+impl From<LintErrors> for GenerateError {
+    fn from(value: LintErrors) -> Self {
+        Self::Lint(value)
+    }
+}
+
+impl From<GenerateError> for io::Error {
+    fn from(value: GenerateError) -> Self {
+        match value {
+            GenerateError::DuplicateName(_) => io::Error::new(io::ErrorKind::InvalidInput, value),
+            GenerateError::Lint(errors) => errors.into(),
+        }
+    }
+}
+
+pub struct GeneratedExample {
+    pub name: String,
+    pub file_name: String,
+    pub content: String,
+}
+
+#[derive(Default)]
+pub struct Generator {
+    options: FormatOptions,
+    variables: Variables,
+}
 
 impl Generator {
-    pub fn generate<F, S>() -> Vec<String>
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_indent(mut self, indent: Indent) -> Self {
+        self.options = self.options.with_indent(indent);
+        self
+    }
+
+    /// Values for placeholder nodes, unbound placeholders are written as is.
+    pub fn with_variables(mut self, variables: Variables) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// Fails if several examples have the same name or any has values the format can't
+    /// represent, see [`Generator::lint`] for warnings.
+    pub fn generate<F, S>(&self) -> Result<Vec<GeneratedExample>, GenerateError>
     where
         F: NodeFormatter,
         S: NodeSchema,
     {
-        S::examples()
+        Ok(self
+            .checked_examples::<F, S>()?
             .into_iter()
-            .flat_map(|Example(nodes)| {
-                nodes
+            .map(|(name, example)| {
+                let mut content = String::new();
+                self.stream::<F, _>(example, |str| content.write_str(str))
+                    .expect("Writing to a String doesn't fail");
+
+                GeneratedExample {
+                    file_name: format!("{name}.{}", F::EXTENSION),
+                    name,
+                    content,
+                }
+            })
+            .collect())
+    }
+
+    /// Generates the examples once per environment with its variables over the generator's, named
+    /// after the environment, or `<example>_<environment>` if the schema has several examples.
+    pub fn generate_environments<F, S>(
+        &self,
+        environments: impl IntoIterator<Item = (impl Into<String>, Variables)>,
+    ) -> Result<Vec<GeneratedExample>, GenerateError>
+    where
+        F: NodeFormatter,
+        S: NodeSchema,
+    {
+        let mut res = vec![];
+
+        for (environment, variables) in environments {
+            let environment = environment.into();
+            let generated = Self {
+                options: self.options,
+                variables: self.variables.clone().merge(variables),
+            }
+            .generate::<F, S>()?;
+            let single = generated.len() == 1;
+
+            res.extend(
+                generated
                     .into_iter()
-                    .map(|node| F::format_node(node))
-                    .reduce(DocStr::merge)
+                    .map(|GeneratedExample { name, content, .. }| {
+                        let name = match single {
+                            true => environment.clone(),
+                            false => format!("{name}_{environment}"),
+                        };
+
+                        GeneratedExample {
+                            file_name: format!("{name}.{}", F::EXTENSION),
+                            name,
+                            content,
+                        }
+                    }),
+            );
+        }
+
+        check_unique_names(res.iter().map(|example| example.name.as_str()))?;
+
+        Ok(res)
+    }
+
+    /// Writes every example to `<name>.<extension>` in `dir`, failing before anything is written
+    /// if a name isn't a plain file name, or on [`GenerateError`].
+    pub fn write_files<F, S>(&self, dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>>
+    where
+        F: NodeFormatter,
+        S: NodeSchema,
+    {
+        for (name, _) in Self::named_examples::<S>() {
+            check_file_name(&name)?;
+        }
+
+        let examples = self.checked_examples::<F, S>()?;
+
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        examples
+            .into_iter()
+            .map(|(name, example)| {
+                let path = dir.join(format!("{name}.{}", F::EXTENSION));
+                let mut writer = BufWriter::new(File::create(&path)?);
+
+                self.stream::<F, _>(example, |str| writer.write_all(str.as_bytes()))?;
+                writer.flush()?;
+
+                Ok(path)
             })
-            .map(|doc_str| doc_str.to_string())
-            .collect::<Vec<_>>()
+            .collect()
+    }
+
+    /// Streams every example of the schema as one document per example, the same content
+    /// [`Generator::generate`] would produce, separated by [`NodeFormatter::DOCUMENT_SEPARATOR`].
+    /// Fails for several examples in formats without one, or on [`GenerateError`].
+    pub fn write_to<F, S>(&self, writer: &mut impl io::Write) -> io::Result<()>
+    where
+        F: NodeFormatter,
+        S: NodeSchema,
+    {
+        let examples = self.checked_examples::<F, S>()?;
+
+        if examples.len() > 1 && F::DOCUMENT_SEPARATOR.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} has no document separator to write several examples into one stream",
+                    F::EXTENSION
+                ),
+            ));
+        }
+
+        for (index, (_, example)) in examples.into_iter().enumerate() {
+            if let (true, Some(separator)) = (index > 0, F::DOCUMENT_SEPARATOR) {
+                let line_ending = self.options.line_ending.as_str();
+
+                if !self.options.trailing_newline {
+                    writer.write_all(line_ending.as_bytes())?;
+                }
+
+                writer.write_all(format!("{separator}{line_ending}").as_bytes())?;
+            }
+
+            self.stream::<F, _>(example, |str| writer.write_all(str.as_bytes()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Fails on [`LintErrors`], before anything is written.
+    pub fn write_example_to<F>(
+        &self,
+        example: Example,
+        writer: &mut impl io::Write,
+    ) -> io::Result<()>
+    where
+        F: NodeFormatter,
+    {
+        let name = example
+            .name
+            .clone()
+            .unwrap_or_else(|| Example::DEFAULT_NAME.to_string());
+        let example = example.prepare(&self.variables);
+        Self::check::<F>([(name, &example)])?;
+
+        self.stream::<F, _>(example, |str| writer.write_all(str.as_bytes()))
+    }
+
+    /// Writes the example as is, even values the format can't represent, see
+    /// [`Generator::lint`].
+    pub fn write_example_fmt<F>(
+        &self,
+        example: Example,
+        writer: &mut impl fmt::Write,
+    ) -> fmt::Result
+    where
+        F: NodeFormatter,
+    {
+        self.stream::<F, _>(example.prepare(&self.variables), |str| {
+            writer.write_str(str)
+        })
+    }
+
+    /// Writes the lines of a prepared example as soon as their block of siblings is laid out.
+    fn stream<F, E>(
+        &self,
+        example: Example,
+        mut write: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        F: NodeFormatter,
+    {
+        let Example {
+            description, nodes, ..
+        } = example;
+        let line_ending = self.options.line_ending.as_str();
+        let header = description.map(|description| {
+            F::format_comment(
+                F::prepare_comment(CommentNode(description), 0, &self.options),
+                &self.options,
+            )
+            .merge(docstr_empty!())
+        });
+
+        let mut empty = true;
+        let mut previous_blank = false;
+
+        for doc_str in header
+            .into_iter()
+            .chain(F::format_blocks(nodes, &self.options))
+        {
+            for line in doc_str.lines() {
+                let line = match self.options.trim_trailing_whitespace {
+                    true => line.trim_end(),
+                    false => line,
+                };
+                let blank = line.trim().is_empty();
+
+                if blank && previous_blank && self.options.collapse_blank_lines {
+                    continue;
+                }
+
+                if !empty {
+                    write(line_ending)?;
+                }
+
+                write(line)?;
+                empty = false;
+                previous_blank = blank;
+            }
+        }
+
+        match self.options.trailing_newline && !empty {
+            true => write(line_ending),
+            false => Ok(()),
+        }
+    }
+
+    /// Problems of the schema's examples in the given format, along with the example name.
+    pub fn lint<F, S>(&self) -> Vec<(String, Lint)>
+    where
+        F: NodeFormatter,
+        S: NodeSchema,
+    {
+        Self::named_examples::<S>()
+            .flat_map(|(name, example)| {
+                F::lint(&example.prepare(&self.variables).nodes)
+                    .into_iter()
+                    .map(move |lint| (name.clone(), lint))
+            })
+            .collect()
+    }
+
+    /// Prepared examples of the schema, if their names are unique and the format can represent
+    /// all of them.
+    fn checked_examples<F, S>(&self) -> Result<Vec<(String, Example)>, GenerateError>
+    where
+        F: NodeFormatter,
+        S: NodeSchema,
+    {
+        let examples = Self::named_examples::<S>()
+            .map(|(name, example)| (name, example.prepare(&self.variables)))
+            .collect::<Vec<_>>();
+        check_unique_names(examples.iter().map(|(name, _)| name.as_str()))?;
+        Self::check::<F>(
+            examples
+                .iter()
+                .map(|(name, example)| (name.clone(), example)),
+        )?;
+
+        Ok(examples)
+    }
+
+    fn check<'a, F>(
+        examples: impl IntoIterator<Item = (String, &'a Example)>,
+    ) -> Result<(), LintErrors>
+    where
+        F: NodeFormatter,
+    {
+        let errors = examples
+            .into_iter()
+            .flat_map(|(name, example)| {
+                F::lint(&example.nodes)
+                    .into_iter()
+                    .filter(|lint| lint.severity == Severity::Error)
+                    .map(move |lint| (name.clone(), lint))
+            })
+            .collect::<Vec<_>>();
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(LintErrors(errors)),
+        }
+    }
+
+    fn named_examples<S>() -> impl Iterator<Item = (String, Example)>
+    where
+        S: NodeSchema,
+    {
+        let examples = S::examples().into_iter().collect::<Vec<_>>();
+        let single = examples.len() == 1;
+
+        examples
+            .into_iter()
+            .enumerate()
+            .map(move |(index, example)| {
+                let name = example.name.clone().unwrap_or_else(|| match single {
+                    true => Example::DEFAULT_NAME.to_string(),
+                    false => format!("{}_{}", Example::DEFAULT_NAME, index + 1),
+                });
+
+                (name, example)
+            })
+    }
+}
+
+/// Examples are keyed by name, which become file names.
+fn check_unique_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Result<(), GenerateError> {
+    let mut seen = HashSet::new();
+
+    match names.into_iter().find(|name| !seen.insert(*name)) {
+        Some(name) => Err(GenerateError::DuplicateName(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Example names become file names, so they can't point anywhere else.
+fn check_file_name(name: &str) -> io::Result<()> {
+    let is_plain = !matches!(name, "" | "." | "..")
+        && !name.contains(['/', '\\', '\0', std::path::MAIN_SEPARATOR]);
+
+    match is_plain {
+        true => Ok(()),
+        false => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Example name {name:?} is not a plain file name"),
+        )),
     }
 }
 
@@ -59,31 +499,413 @@ mod test {
     use macros::node;
 
     use crate::{
-        comment, float, integer,
-        node::formatter::{toml::TomlNodeFormatter, yaml::YamlFormatter},
+        comment, empty, float, integer, key,
+        node::{
+            formatter::{
+                options::{CommentStyle, FormatOptions, LineEnding},
+                toml::TomlNodeFormatter,
+                yaml::YamlFormatter,
+                NodeFormatter,
+            },
+            types::{
+                placeholder::{PlaceholderSyntax, Variables},
+                secret::SecretPlaceholder,
+            },
+        },
+        placeholder, secret, string, table,
     };
 
-    use super::{Example, Generator, NodeSchema};
+    use super::{Example, GeneratedExample, Generator, NodeSchema};
+
+    /// Formats one example as is, without a schema around it.
+    fn format<F: NodeFormatter>(generator: &Generator, example: Example) -> String {
+        let mut content = String::new();
+        generator
+            .write_example_fmt::<F>(example, &mut content)
+            .unwrap();
+
+        content
+    }
 
     struct TestSchema {}
 
     impl NodeSchema for TestSchema {
         fn examples() -> impl IntoIterator<Item = Example> {
-            [Example::from_nodes([
-                node!(comment!("This", "is", "a", "multiline", "comment")),
-                node!(integer!(5).with_name("test_int")),
-                node!(float!(4.6).with_name("test_float")),
-            ])]
+            [
+                Example::from_nodes([
+                    node!(comment!("This", "is", "a", "multiline", "comment")),
+                    node!(
+                        integer!(5).with_name("test_int"),
+                        comments = { right: "Aligned" }
+                    ),
+                    node!(
+                        float!(4.6).with_name("test_float"),
+                        comments = { right: "comments" }
+                    ),
+                    node!(
+                        integer!(30).with_name("test_commented_int"),
+                        commented = true,
+                        comments = { right: "Optional" }
+                    ),
+                ]),
+                Example::named("minimal")
+                    .with_description("Only the required settings")
+                    .add_number(integer!(5).with_name("test_int")),
+            ]
         }
     }
 
     #[test]
     fn print_out_test_schema() {
-        let generated_toml_str = Generator::generate::<TomlNodeFormatter, TestSchema>().join("\n");
-        let generated_yaml_str = Generator::generate::<YamlFormatter, TestSchema>().join("\n");
+        let generated_toml = Generator::new()
+            .generate::<TomlNodeFormatter, TestSchema>()
+            .unwrap();
+        let generated_yaml = Generator::new()
+            .generate::<YamlFormatter, TestSchema>()
+            .unwrap();
+
+        for GeneratedExample {
+            file_name, content, ..
+        } in generated_toml.into_iter().chain(generated_yaml)
+        {
+            println!("Generated {file_name}:\n{content}");
+            println!();
+        }
+    }
+
+    #[test]
+    fn examples_are_keyed_by_name() {
+        let file_names = Generator::new()
+            .generate::<TomlNodeFormatter, TestSchema>()
+            .unwrap()
+            .into_iter()
+            .map(|GeneratedExample { file_name, .. }| file_name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(file_names, ["example_1.toml", "minimal.toml"]);
+    }
+
+    #[test]
+    fn write_files_writes_generated_examples() {
+        let dir = std::env::temp_dir().join(format!("config_example_out_{}", std::process::id()));
+        let generator = Generator::new();
+
+        let paths = generator
+            .write_files::<TomlNodeFormatter, TestSchema>(&dir)
+            .unwrap();
+        let written = paths
+            .iter()
+            .map(|path| {
+                (
+                    path.strip_prefix(&dir)
+                        .unwrap()
+                        .to_string_lossy()
+                        .into_owned(),
+                    std::fs::read_to_string(path).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let generated = generator
+            .generate::<TomlNodeFormatter, TestSchema>()
+            .unwrap()
+            .into_iter()
+            .map(
+                |GeneratedExample {
+                     file_name, content, ..
+                 }| (file_name, content),
+            )
+            .collect::<Vec<_>>();
+
+        assert_eq!(written, generated);
+    }
+
+    #[test]
+    fn write_files_rejects_unsafe_names() {
+        struct EscapingSchema;
+
+        impl NodeSchema for EscapingSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::named("../x")]
+            }
+        }
+
+        struct DuplicateSchema;
+
+        impl NodeSchema for DuplicateSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::named("x"), Example::named("x")]
+            }
+        }
+
+        let dir = std::env::temp_dir().join(format!("config_example_{}", std::process::id()));
+        let generator = Generator::new();
+
+        assert_eq!(
+            generator
+                .write_files::<TomlNodeFormatter, EscapingSchema>(&dir)
+                .unwrap_err()
+                .to_string(),
+            "Example name \"../x\" is not a plain file name"
+        );
+        assert_eq!(
+            generator
+                .write_files::<TomlNodeFormatter, DuplicateSchema>(&dir)
+                .unwrap_err()
+                .to_string(),
+            "Several examples are named \"x\""
+        );
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn duplicate_names_fail_generation() {
+        struct DuplicateSchema;
+
+        impl NodeSchema for DuplicateSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::named("x"), Example::named("x")]
+            }
+        }
+
+        struct SuffixedSchema;
+
+        impl NodeSchema for SuffixedSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::named("a"), Example::named("a_b")]
+            }
+        }
+
+        let generator = Generator::new();
+
+        assert_eq!(
+            generator
+                .generate::<TomlNodeFormatter, DuplicateSchema>()
+                .err()
+                .unwrap()
+                .to_string(),
+            "Several examples are named \"x\""
+        );
+        assert_eq!(
+            generator
+                .write_to::<YamlFormatter, DuplicateSchema>(&mut vec![])
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+        // `a` in `b_c` and `a_b` in `c`
+        assert_eq!(
+            generator
+                .generate_environments::<TomlNodeFormatter, SuffixedSchema>([
+                    ("b_c", Variables::new()),
+                    ("c", Variables::new()),
+                ])
+                .err()
+                .unwrap()
+                .to_string(),
+            "Several examples are named \"a_b_c\""
+        );
+    }
+
+    #[test]
+    fn options_control_line_endings() {
+        let generator = Generator::new().with_options(
+            FormatOptions::default()
+                .with_line_ending(LineEnding::CrLf)
+                .with_comment_style(CommentStyle::Compact),
+        );
+        let minimal = generator
+            .generate::<TomlNodeFormatter, TestSchema>()
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(
+            minimal.content,
+            "#Only the required settings\r\n\r\ntest_int = 5\r\n"
+        );
+    }
+
+    #[test]
+    fn streamed_output_matches_generated() {
+        let generator = Generator::new();
+        let generated = generator
+            .generate::<YamlFormatter, TestSchema>()
+            .unwrap()
+            .into_iter()
+            .map(|GeneratedExample { content, .. }| content)
+            .collect::<Vec<_>>()
+            .join("---\n");
+
+        let mut streamed = vec![];
+        generator
+            .write_to::<YamlFormatter, TestSchema>(&mut streamed)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(streamed).unwrap(), generated);
+        assert_eq!(
+            generator
+                .write_to::<TomlNodeFormatter, TestSchema>(&mut vec![])
+                .unwrap_err()
+                .to_string(),
+            "toml has no document separator to write several examples into one stream"
+        );
+    }
+
+    #[test]
+    fn whitespace_cleanup() {
+        let example = || {
+            Example::from_nodes([
+                node!(integer!(1).with_name("a"), comments = { right: [""] }),
+                node!(empty!(), tabs = 1),
+                node!(empty!(3)),
+                node!(integer!(2).with_name("b")),
+            ])
+        };
+        let generate = |options| {
+            format::<TomlNodeFormatter>(&Generator::new().with_options(options), example())
+        };
+
+        assert_eq!(
+            generate(FormatOptions::default().with_collapse_blank_lines(true)),
+            "a = 1 #\n\nb = 2\n"
+        );
+        assert_eq!(
+            generate(
+                FormatOptions::default()
+                    .with_trim_trailing_whitespace(false)
+                    .with_trailing_newline(false)
+            ),
+            "a = 1 #\n    \n\n\n\nb = 2"
+        );
+    }
+
+    #[test]
+    fn lint_errors_fail_generation() {
+        struct WideSchema;
+
+        impl NodeSchema for WideSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::named("wide").add_number(integer!(u64::MAX).with_name("seed"))]
+            }
+        }
+
+        let generator = Generator::new();
+
+        assert_eq!(
+            generator
+                .generate::<TomlNodeFormatter, WideSchema>()
+                .err()
+                .unwrap()
+                .to_string(),
+            "wide: error: seed: 18446744073709551615 is out of the toml integer range"
+        );
+        assert_eq!(
+            generator
+                .write_to::<TomlNodeFormatter, WideSchema>(&mut vec![])
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::InvalidData
+        );
+        assert!(generator.generate::<YamlFormatter, WideSchema>().is_ok());
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let example = Example::from_nodes([
+            node!(
+                string!("hunter2").with_name("password"),
+                comments = { top: "Login" }
+            ),
+            node!(table!(
+                "api",
+                [
+                    node!(integer!(1234567).with_name("token")),
+                    node!(secret!("key")
+                        .with_placeholder(SecretPlaceholder::EnvVar("API_KEY".into()))
+                        .with_source("Create one at https://example.com/keys")),
+                ]
+            )),
+        ])
+        .with_secret("password")
+        .with_secret(key!("api", "token"));
+
+        let content = format::<TomlNodeFormatter>(&Generator::new(), example);
+
+        assert!(!content.contains("hunter2") && !content.contains("1234567"));
+        assert_eq!(
+            content,
+            [
+                "# Login",
+                "password = \"<your-password>\" # Secret, don't commit the real value",
+                "api.token = \"<your-token>\" # Secret, don't commit the real value",
+                "# Secret, set the API_KEY environment variable",
+                "# Create one at https://example.com/keys",
+                "api.key = \"${API_KEY}\"",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn environments_bind_placeholders() {
+        struct ServerSchema;
+
+        impl NodeSchema for ServerSchema {
+            fn examples() -> impl IntoIterator<Item = Example> {
+                [Example::from_nodes([
+                    node!(placeholder!("hostname").with_name("host")),
+                    node!(placeholder!("PORT")
+                        .with_name("port")
+                        .with_syntax(PlaceholderSyntax::Env)),
+                    node!(table!(
+                        "db",
+                        [node!(placeholder!("db_password").with_name("password"))]
+                    )),
+                ])
+                .with_secret(key!("db", "password"))]
+            }
+        }
+
+        let generated = Generator::new()
+            .with_variables(Variables::new().with("PORT", integer!(80)))
+            .generate_environments::<TomlNodeFormatter, ServerSchema>([
+                (
+                    "production",
+                    Variables::new()
+                        .with("hostname", string!("example.com"))
+                        .with("PORT", integer!(443))
+                        .with("db_password", string!("hunter2")),
+                ),
+                ("staging", Variables::new()),
+            ])
+            .unwrap()
+            .into_iter()
+            .map(
+                |GeneratedExample {
+                     file_name, content, ..
+                 }| (file_name, content),
+            )
+            .collect::<Vec<_>>();
 
-        println!("Generated TOML example:\n{generated_toml_str}");
-        println!();
-        println!("Generated YAML example:\n{generated_yaml_str}");
+        assert_eq!(
+            generated,
+            [
+                (
+                    "production.toml".to_string(),
+                    "host = \"example.com\"\nport = 443\n\
+                     db.password = \"<your-password>\" # Secret, don't commit the real value\n"
+                        .to_string()
+                ),
+                (
+                    "staging.toml".to_string(),
+                    "host = \"{{ hostname }}\"\nport = 80\n\
+                     db.password = \"{{ db_password }}\"\n"
+                        .to_string()
+                ),
+            ]
+        );
     }
 }
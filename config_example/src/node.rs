@@ -1,17 +1,25 @@
 pub mod formatter;
+pub mod key;
 pub mod types;
 
 use crate::{comment, empty, float, integer, util::DocStr};
 
-use self::types::{
-    comment::CommentNode,
-    number::{IntoFloatType, IntoIntegerType},
-    NodeType,
+use self::{
+    key::Key,
+    types::{
+        choice::{ChoiceNode, ChoiceVariant},
+        comment::CommentNode,
+        number::{IntoFloatType, IntoIntegerType},
+        placeholder::Variables,
+        NodeType,
+    },
 };
 
+#[derive(Clone)]
 pub struct Node {
     ty: NodeType,
     tabs: usize,
+    commented: bool,
     comments: Comments,
 }
 
@@ -20,6 +28,7 @@ impl Node {
         Self {
             ty: ty.into(),
             tabs: 0,
+            commented: false,
             comments: Default::default(),
         }
     }
@@ -36,6 +45,13 @@ impl Node {
         Self::new(float!(float))
     }
 
+    pub fn choice(
+        selected: impl Into<String>,
+        variants: impl IntoIterator<Item = impl Into<ChoiceVariant>>,
+    ) -> Self {
+        Self::new(ChoiceNode::new(selected, variants))
+    }
+
     pub fn empty() -> Self {
         Self::new(empty!())
     }
@@ -44,11 +60,30 @@ impl Node {
         Self::new(empty!(amount))
     }
 
+    /// See [`NodeType::substitute`].
+    pub fn substitute(&mut self, variables: &Variables) {
+        self.ty.substitute(variables);
+    }
+
+    /// See [`NodeType::redact`].
+    pub fn redact(&mut self, secrets: &[Key]) {
+        self.ty.redact(None, secrets);
+    }
+
     pub fn with_tabs(mut self, tabs: usize) -> Self {
         self.tabs = tabs;
         self
     }
 
+    pub fn with_commented(mut self, commented: bool) -> Self {
+        self.commented = commented;
+        self
+    }
+
+    pub fn commented_out(self) -> Self {
+        self.with_commented(true)
+    }
+
     pub fn with_top_comment(mut self, comment: impl Into<CommentNode>) -> Self {
         self.comments.top = Some(comment.into());
         self
@@ -60,7 +95,7 @@ impl Node {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Comments {
     top: Option<CommentNode>,
     right: Option<CommentNode>,
@@ -1,54 +1,580 @@
-use crate::{docstr, docstr_empty, util::DocStr};
+use crate::{
+    docstr, docstr_empty,
+    util::{doc::Doc, markdown, str_width, DocStr, Indent},
+};
 
-use super::{types::number::NumberNode, CommentNode, Comments, Node, NodeType};
+use self::{
+    block::{FormattedNode, RenderedNode},
+    lint::Lint,
+    options::{FormatOptions, QuoteStyle},
+};
 
+use super::{
+    key::Key,
+    types::{
+        array::ArrayNode,
+        choice::ChoiceNode,
+        number::{NumberNode, NumberSyntax, NumberType},
+        optional::OptionalNode,
+        path::PathNode,
+        placeholder::PlaceholderNode,
+        quantity::Quantity,
+        raw::RawNode,
+        secret::SecretNode,
+        string::StringNode,
+        table::TableNode,
+        tagged::TaggedNode,
+    },
+    CommentNode, Comments, Node, NodeType,
+};
+
+pub mod block;
+pub mod lint;
+pub mod options;
 #[cfg(feature = "toml")]
 pub mod toml;
 #[cfg(feature = "yaml")]
 pub mod yaml;
 
 pub trait NodeFormatter {
+    const EXTENSION: &'static str;
+    const DEFAULT_INDENT: Indent;
     const NAMED_NODE_SEPARATOR: &'static str;
+    const COMMENT_MARKER: &'static str;
+    const NUMBER_SYNTAX: NumberSyntax;
+    /// Separates documents in one stream, `None` if the format has a single document per file.
+    const DOCUMENT_SEPARATOR: Option<&'static str>;
+
+    fn format_block(
+        nodes: impl IntoIterator<Item = Node>,
+        options: &FormatOptions,
+    ) -> Option<DocStr> {
+        Self::format_blocks(nodes, options).reduce(DocStr::merge)
+    }
+
+    /// Lazily formats nodes one block of siblings (separated by blank lines) at a time, so that
+    /// the output can be streamed while still being laid out block-aware.
+    fn format_blocks(
+        nodes: impl IntoIterator<Item = Node>,
+        options: &FormatOptions,
+    ) -> impl Iterator<Item = DocStr> {
+        let mut nodes = nodes
+            .into_iter()
+            .map(move |node| Self::format_node_parts(node, options))
+            .peekable();
+
+        std::iter::from_fn(move || {
+            let mut block = vec![];
+
+            while let Some(node) = nodes.next_if(|node| !node.is_blank()) {
+                block.push(node);
+            }
+
+            match block.is_empty() {
+                true => nodes
+                    .next()
+                    .map(|node| node.render(options.max_width).into_doc_str()),
+                false => Some(Self::layout_block(block, options)),
+            }
+        })
+    }
+
+    fn layout_block(mut nodes: Vec<FormattedNode>, options: &FormatOptions) -> DocStr {
+        if options.align_keys {
+            block::align_keys(&mut nodes);
+        }
+
+        let mut nodes = nodes
+            .into_iter()
+            .map(|node| node.render(options.max_width))
+            .collect::<Vec<_>>();
+
+        if options.align_right_comments {
+            block::align_right_comments(&mut nodes, options.right_comment_max_column);
+        }
 
-    fn format_node(
+        nodes
+            .into_iter()
+            .map(RenderedNode::into_doc_str)
+            .reduce(DocStr::merge)
+            .unwrap_or_else(|| docstr_empty!(0))
+    }
+
+    fn format_node(node: Node, options: &FormatOptions) -> DocStr {
+        Self::format_node_parts(node, options)
+            .render(options.max_width)
+            .into_doc_str()
+    }
+
+    fn format_node_parts(
         Node {
             tabs,
             ty,
+            commented,
             comments: Comments { top, right },
         }: Node,
-    ) -> DocStr {
-        let res = match ty {
-            NodeType::Comment(comment) => Self::format_comment(comment.clone()),
-            NodeType::Empty => Self::format_empty(),
-            NodeType::EmptyMultiline(amount) => Self::format_empty_multiline(amount),
-            NodeType::Number(num) => Self::format_number(num),
+        options: &FormatOptions,
+    ) -> FormattedNode {
+        let indent = Self::indent(options);
+        let indent_width = str_width(&indent.repeat(tabs));
+        let commented_example = matches!(
+            &ty,
+            NodeType::Optional(OptionalNode { is_set: false, .. })
+                if Self::format_null(options).is_none()
+        );
+        // Only single-line `key = value` nodes are aligned
+        let key_width = match commented
+            || commented_example
+            || matches!(ty, NodeType::Table(_) | NodeType::Tagged(_))
+        {
+            true => None,
+            false => ty
+                .key()
+                .and_then(|key| Self::aligned_key_width(key, options))
+                .map(|width| indent_width + width),
+        };
+        let (top, right) = match Self::auto_comment(&ty, options) {
+            // Short generated comments go to the right, unless it's already taken
+            Some(auto) if auto.lines().count() == 1 && right.is_none() => {
+                (top, Some(CommentNode(auto)))
+            }
+            Some(auto) => (
+                Some(match top {
+                    Some(CommentNode(top)) => CommentNode(top.merge(auto)),
+                    None => CommentNode(auto),
+                }),
+                right,
+            ),
+            None => (top, right),
+        };
+
+        let body = Self::format_type(ty, indent_width, options);
+
+        FormattedNode {
+            top: top.map(|top| {
+                Self::format_comment(Self::prepare_comment(top, indent_width, options), options)
+                    .tabbed(tabs, indent)
+            }),
+            body: match commented {
+                true => Self::comment_out(body, options),
+                false => body,
+            },
+            right: right
+                .map(|right| Self::format_comment(Self::render_markdown(right, options), options)),
+            tabs,
+            indent,
+            key_width,
+        }
+    }
+
+    fn format_type(ty: NodeType, indent_width: usize, options: &FormatOptions) -> Doc {
+        match ty {
+            NodeType::Comment(comment) => Self::format_comment(
+                Self::prepare_comment(comment, indent_width, options),
+                options,
+            )
+            .into(),
+            NodeType::Empty => Self::format_empty(options),
+            NodeType::EmptyMultiline(amount) => Self::format_empty_multiline(amount, options),
+            NodeType::Number(num) => Self::format_number(num, options),
+            NodeType::String(string) => Self::format_string_node(string, options),
+            NodeType::Choice(choice) => Self::format_choice(choice, options),
+            NodeType::Table(table) => Self::format_table(table, indent_width, options),
+            NodeType::Tagged(tagged) => Self::format_tagged(tagged, options),
+            NodeType::Optional(optional) => Self::format_optional(optional, indent_width, options),
+            NodeType::Duration(duration) => Self::format_quantity(duration, options),
+            NodeType::ByteSize(byte_size) => Self::format_quantity(byte_size, options),
+            NodeType::Path(path) => Self::format_path(path, options),
+            NodeType::Secret(secret) => Self::format_secret(secret, options),
+            NodeType::Placeholder(placeholder) => Self::format_placeholder(placeholder, options),
+            NodeType::Raw(raw) => Self::format_raw(raw, options),
+            NodeType::Array(array) => Self::format_array(array, indent_width, options),
+        }
+    }
+
+    /// Problems with the nodes in this format, e.g. values it can't represent.
+    fn lint<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Vec<Lint> {
+        let mut lints = vec![];
+
+        for node in nodes {
+            Self::lint_type(&node.ty, None, &mut lints);
+        }
+
+        lints
+    }
+
+    fn lint_type(ty: &NodeType, parent: Option<&Key>, lints: &mut Vec<Lint>) {
+        let join = |parent: Option<&Key>, key: Option<&Key>| match (parent, key) {
+            (Some(parent), Some(key)) => Some(parent.join(key)),
+            (parent, key) => key.or(parent).cloned(),
         };
+        let key = join(parent, ty.key());
+
+        match ty {
+            NodeType::Number(number) => {
+                lints.extend(Self::lint_range(number.ty, key.as_ref()));
+
+                for problem in number.check_bounds() {
+                    lints.push(Lint::warning(key.clone(), problem));
+                }
+            }
+            NodeType::Table(TableNode { nodes, .. }) => {
+                for node in nodes {
+                    Self::lint_type(&node.ty, key.as_ref(), lints);
+                }
+            }
+            NodeType::Tagged(TaggedNode {
+                tagging, variants, ..
+            }) => {
+                for variant in variants {
+                    let fields_key = join(key.as_ref(), tagging.fields_key(&variant.tag).as_ref());
 
-        match (top, right) {
-            (None, None) => res,
-            (None, Some(CommentNode(right_str))) => res.attach_right(right_str),
-            (Some(CommentNode(top_str)), None) => top_str.merge(res),
-            (Some(CommentNode(top_str)), Some(CommentNode(right_str))) => {
-                top_str.merge(res).attach_right(right_str)
+                    for node in &variant.fields {
+                        Self::lint_type(&node.ty, fields_key.as_ref(), lints);
+                    }
+                }
+            }
+            NodeType::Optional(OptionalNode { example, .. }) => {
+                Self::lint_type(example, parent, lints)
+            }
+            NodeType::Duration(duration) => Self::lint_quantity(duration, key, lints),
+            NodeType::ByteSize(byte_size) => Self::lint_quantity(byte_size, key, lints),
+            NodeType::Array(ArrayNode { items, .. }) => {
+                for item in items {
+                    Self::lint_type(item, key.as_ref(), lints);
+                }
             }
+            NodeType::Raw(raw) if raw.text(Self::EXTENSION).is_none() => lints.push(Lint::warning(
+                key,
+                format!("raw node has no {} text and is left out", Self::EXTENSION),
+            )),
+            NodeType::Comment(_)
+            | NodeType::Empty
+            | NodeType::EmptyMultiline(_)
+            | NodeType::String(_)
+            | NodeType::Choice(_)
+            | NodeType::Path(_)
+            | NodeType::Secret(_)
+            | NodeType::Placeholder(_)
+            | NodeType::Raw(_) => {}
         }
-        .tabbed(tabs)
     }
 
-    fn format_comment(comment: CommentNode) -> DocStr;
+    fn lint_range(ty: NumberType, key: Option<&Key>) -> Option<Lint> {
+        (!ty.fits(&Self::NUMBER_SYNTAX)).then(|| {
+            Lint::error(
+                key.cloned(),
+                format!("{ty} is out of the {} integer range", Self::EXTENSION),
+            )
+        })
+    }
+
+    /// Integer amounts have to fit the format's integers like any number.
+    fn lint_quantity(quantity: &impl Quantity, key: Option<Key>, lints: &mut Vec<Lint>) {
+        if let Some(amount) = quantity.amount() {
+            lints.extend(Self::lint_range(NumberType::integer(amount), key.as_ref()));
+        }
 
-    fn format_empty() -> DocStr {
-        docstr_empty!()
+        lints.extend(
+            quantity
+                .truncation()
+                .map(|problem| Lint::warning(key, problem)),
+        );
     }
 
-    fn format_empty_multiline(amount: usize) -> DocStr {
-        docstr_empty!(amount)
+    /// Comment generated from the node's own metadata, e.g. the allowed variants of a choice.
+    fn auto_comment(ty: &NodeType, options: &FormatOptions) -> Option<DocStr> {
+        match ty {
+            NodeType::Choice(choice) => choice.describe_variants(),
+            NodeType::Number(number) => number.describe_bounds(&Self::NUMBER_SYNTAX),
+            NodeType::Duration(duration) => Some(duration.describe_syntax()),
+            NodeType::ByteSize(byte_size) => Some(byte_size.describe_syntax()),
+            NodeType::Secret(secret) => Some(secret.describe()),
+            NodeType::Path(path) => path.expanded_windows().map(|windows| {
+                docstr!(format!(
+                    "On Windows: {}",
+                    Self::format_path_string(&windows, path.literal, options)
+                ))
+            }),
+            NodeType::Optional(OptionalNode { example, .. }) => {
+                Self::auto_comment(example, options)
+            }
+            NodeType::Comment(_)
+            | NodeType::Empty
+            | NodeType::EmptyMultiline(_)
+            | NodeType::String(_)
+            | NodeType::Table(_)
+            | NodeType::Tagged(_)
+            | NodeType::Placeholder(_)
+            | NodeType::Raw(_)
+            | NodeType::Array(_) => None,
+        }
+    }
+
+    /// Width of the key on the first line of a named node, `None` if it can't be aligned.
+    fn aligned_key_width(key: &Key, options: &FormatOptions) -> Option<usize> {
+        Some(str_width(&Self::format_key(key, options)))
+    }
+
+    fn indent(options: &FormatOptions) -> Indent {
+        options.indent.unwrap_or(Self::DEFAULT_INDENT)
+    }
+
+    /// Text every non-empty comment line starts with, comments and commented out nodes alike.
+    fn comment_prefix(options: &FormatOptions) -> String {
+        options.comment_style.line_start(Self::COMMENT_MARKER)
     }
 
-    fn format_number(NumberNode { ty, name }: NumberNode) -> DocStr {
-        docstr!(match name {
-            Some(name) => format!("{name}{}{ty}", Self::NAMED_NODE_SEPARATOR),
-            None => ty.to_string(),
+    fn format_comment(CommentNode(comment): CommentNode, options: &FormatOptions) -> DocStr {
+        let prefix = Self::comment_prefix(options);
+
+        comment.map(|s| match s.is_empty() {
+            true => prefix.trim_end().to_string(),
+            false => format!("{prefix}{s}"),
         })
     }
+
+    /// Comments every line of a laid out value out, e.g. for commented nodes.
+    fn comment_out(doc: Doc, options: &FormatOptions) -> Doc {
+        doc.prefixed(Self::comment_prefix(options))
+    }
+
+    /// Renders markdown and wraps the comment to `max_width`, taking the indentation and comment
+    /// marker into account.
+    fn prepare_comment(
+        comment: CommentNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> CommentNode {
+        let prefix_width = str_width(&Self::comment_prefix(options));
+        let width = options
+            .max_width
+            .map(|max_width| max_width.saturating_sub(indent_width + prefix_width));
+
+        match (options.render_markdown, width) {
+            // Code blocks keep their lines
+            (true, Some(width)) => CommentNode(markdown::render_wrapped(
+                comment.0,
+                options.link_style,
+                width,
+            )),
+            (false, Some(width)) => CommentNode(comment.0.wrap(width)),
+            (_, None) => Self::render_markdown(comment, options),
+        }
+    }
+
+    fn render_markdown(CommentNode(comment): CommentNode, options: &FormatOptions) -> CommentNode {
+        match options.render_markdown {
+            true => CommentNode(markdown::render_plain(comment, options.link_style)),
+            false => CommentNode(comment),
+        }
+    }
+
+    fn format_empty(_options: &FormatOptions) -> Doc {
+        docstr_empty!().into()
+    }
+
+    fn format_empty_multiline(amount: usize, _options: &FormatOptions) -> Doc {
+        match amount {
+            0 => Doc::Nil,
+            _ => docstr_empty!(amount).into(),
+        }
+    }
+
+    fn format_number(
+        NumberNode {
+            ty, name, style, ..
+        }: NumberNode,
+        options: &FormatOptions,
+    ) -> Doc {
+        let value = Doc::text(ty.format(style, &Self::NUMBER_SYNTAX));
+
+        Self::format_optionally_named(name, value, options)
+    }
+
+    fn format_choice(
+        ChoiceNode { selected, name, .. }: ChoiceNode,
+        options: &FormatOptions,
+    ) -> Doc {
+        let value = Doc::text(Self::format_string(&selected, options));
+
+        Self::format_optionally_named(name, value, options)
+    }
+
+    /// Lays the nodes out as dotted keys under the table's name.
+    fn format_table(
+        TableNode { name, nodes }: TableNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> Doc {
+        let nodes = nodes.into_iter().map(|mut node| {
+            node.ty.prefix_key(&name);
+            node
+        });
+
+        Self::format_block(nodes, &options.nested(indent_width)).map_or(Doc::Nil, Doc::from)
+    }
+
+    /// Selected variant followed by the others commented out, each under its own doc comment.
+    fn format_tagged(tagged: TaggedNode, options: &FormatOptions) -> Doc {
+        let variants = tagged.into_variants().filter_map(|(selected, doc, nodes)| {
+            let body = Doc::from(Self::format_block(nodes, options)?);
+            let body = match selected {
+                true => body,
+                false => Self::comment_out(body, options),
+            };
+
+            let doc = doc.map(|doc| {
+                Doc::from(Self::format_comment(
+                    Self::prepare_comment(CommentNode(doc), 0, options),
+                    options,
+                ))
+            });
+
+            match (doc, body) {
+                (None, Doc::Nil) => None,
+                (Some(doc), Doc::Nil) => Some(doc),
+                (Some(doc), body) => Some(doc.append(Doc::hardline()).append(body)),
+                (None, body) => Some(body),
+            }
+        });
+
+        Doc::join(variants, Doc::hardline())
+    }
+
+    /// Quoted if it has units, a plain integer otherwise.
+    fn format_quantity(quantity: impl Quantity, options: &FormatOptions) -> Doc {
+        let value = match quantity.amount() {
+            Some(_) => quantity.text(),
+            None => Self::format_string(&quantity.text(), options),
+        };
+
+        Self::format_optionally_named(quantity.name().cloned(), Doc::text(value), options)
+    }
+
+    fn format_string_node(StringNode { value, name }: StringNode, options: &FormatOptions) -> Doc {
+        Self::format_optionally_named(
+            name,
+            Doc::text(Self::format_string(&value, options)),
+            options,
+        )
+    }
+
+    fn format_path(path: PathNode, options: &FormatOptions) -> Doc {
+        let value = Self::format_path_string(&path.expanded(), path.literal, options);
+
+        Self::format_optionally_named(path.name, Doc::text(value), options)
+    }
+
+    /// Paths are strings, single quoted ones for `literal` paths, as they usually don't need
+    /// escapes.
+    fn format_path_string(path: &str, literal: bool, options: &FormatOptions) -> String {
+        match literal {
+            true => Self::format_string(path, &options.with_quote_style(QuoteStyle::Single)),
+            false => Self::format_string(path, options),
+        }
+    }
+
+    fn format_secret(
+        SecretNode {
+            name, placeholder, ..
+        }: SecretNode,
+        options: &FormatOptions,
+    ) -> Doc {
+        let value = Doc::text(Self::format_string(&placeholder.text(), options));
+
+        Self::format_optionally_named(name, value, options)
+    }
+
+    fn format_placeholder(placeholder: PlaceholderNode, options: &FormatOptions) -> Doc {
+        let value = Doc::text(Self::format_string(&placeholder.text(), options));
+
+        Self::format_optionally_named(placeholder.name, value, options)
+    }
+
+    /// Emitted as is, only indented with the node.
+    fn format_raw(mut raw: RawNode, _options: &FormatOptions) -> Doc {
+        raw.texts
+            .remove(Self::EXTENSION)
+            .map_or(Doc::Nil, Doc::from)
+    }
+
+    /// Inline when it fits, otherwise one item per line, with a trailing comma.
+    fn format_array(
+        ArrayNode { items, name }: ArrayNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> Doc {
+        let value = match items.is_empty() {
+            true => Doc::text("[]"),
+            false => Doc::text("[")
+                .append(
+                    Doc::softline()
+                        .append(Doc::join(
+                            items
+                                .into_iter()
+                                .map(|item| Self::format_type(item, indent_width, options)),
+                            Doc::text(",").append(Doc::line()),
+                        ))
+                        .nest(1),
+                )
+                .append(Doc::if_break(",", Doc::Nil))
+                .append(Doc::softline())
+                .append("]")
+                .group(),
+        };
+
+        Self::format_optionally_named(name, value, options)
+    }
+
+    /// Unset optional values are named nulls, or their example commented out in formats without
+    /// null.
+    fn format_optional(
+        OptionalNode { example, is_set }: OptionalNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> Doc {
+        match (is_set, Self::format_null(options)) {
+            (true, _) => Self::format_type(*example, indent_width, options),
+            (false, Some(null)) => match example.key() {
+                Some(key) => Self::format_named(key.clone(), null, options),
+                None => null,
+            },
+            (false, None) => {
+                Self::comment_out(Self::format_type(*example, indent_width, options), options)
+            }
+        }
+    }
+
+    /// Literal for unset optional values, `None` if the format has no null.
+    fn format_null(_options: &FormatOptions) -> Option<Doc> {
+        None
+    }
+
+    fn format_string(value: &str, options: &FormatOptions) -> String;
+
+    fn format_key_segment(segment: &str, options: &FormatOptions) -> String;
+
+    fn format_key(key: &Key, options: &FormatOptions) -> String {
+        key.segments()
+            .iter()
+            .map(|segment| Self::format_key_segment(segment, options))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn format_optionally_named(name: Option<Key>, value: Doc, options: &FormatOptions) -> Doc {
+        match name {
+            Some(name) => Self::format_named(name, value, options),
+            None => value,
+        }
+    }
+
+    /// The key is padded for alignment, see [`block::align_keys`].
+    fn format_named(key: Key, value: Doc, options: &FormatOptions) -> Doc {
+        Doc::text(Self::format_key(&key, options))
+            .padded(0)
+            .append(Self::NAMED_NODE_SEPARATOR)
+            .append(value)
+    }
 }
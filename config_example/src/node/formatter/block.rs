@@ -0,0 +1,177 @@
+use crate::util::{doc::Doc, str_width, DocStr, Indent};
+
+/// Node formatted into its parts, so that a block of siblings can be laid out together before
+/// being joined.
+pub struct FormattedNode {
+    pub top: Option<DocStr>,
+    /// Rendered only once the keys of the block are aligned.
+    pub body: Doc,
+    pub right: Option<DocStr>,
+    pub tabs: usize,
+    pub indent: Indent,
+    /// Width of the indented key on the first body line, for named nodes that can be aligned.
+    pub key_width: Option<usize>,
+}
+
+impl FormattedNode {
+    pub fn indent_width(&self) -> usize {
+        str_width(&self.indent.repeat(self.tabs))
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.top.is_none() && self.right.is_none() && self.body.is_blank()
+    }
+
+    /// Renders the body in what's left of `max_width` after its indentation.
+    pub fn render(self, max_width: Option<usize>) -> RenderedNode {
+        let width = max_width.map(|max_width| max_width.saturating_sub(self.indent_width()));
+
+        RenderedNode {
+            top: self.top,
+            body: self
+                .body
+                .render(width, self.indent)
+                .tabbed(self.tabs, self.indent),
+            right: self.right,
+            right_column: None,
+        }
+    }
+}
+
+/// Node with its body laid out, for the right comments of a block to be aligned.
+pub struct RenderedNode {
+    pub top: Option<DocStr>,
+    pub body: DocStr,
+    pub right: Option<DocStr>,
+    /// Column the right comment is padded to, a single space is used otherwise.
+    pub right_column: Option<usize>,
+}
+
+impl RenderedNode {
+    /// Width of the widest body line, where the gutter of the right comment starts.
+    pub fn body_width(&self) -> usize {
+        self.body.lines().map(str_width).max().unwrap_or_default()
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.top.is_none() && self.right.is_none() && self.body.is_blank()
+    }
+
+    pub fn into_doc_str(self) -> DocStr {
+        let Self {
+            top,
+            body,
+            right,
+            right_column,
+        } = self;
+
+        let body = match (right, right_column) {
+            (None, _) => body,
+            (Some(right), None) => body.attach_right(right),
+            (Some(right), Some(column)) => body.attach_right_at(right, column),
+        };
+
+        match top {
+            Some(top) => top.merge(body),
+            None => body,
+        }
+    }
+}
+
+/// Pads keys of consecutive named nodes to the longest one, so their separators line up.
+pub fn align_keys(nodes: &mut [FormattedNode]) {
+    for block in nodes.split_mut(FormattedNode::is_blank) {
+        let Some(width) = block.iter().filter_map(|node| node.key_width).max() else {
+            continue;
+        };
+
+        for node in block.iter_mut() {
+            if node.key_width.is_some() {
+                let indent_width = node.indent_width();
+                node.body.set_padding(width - indent_width);
+                node.key_width = Some(width);
+            }
+        }
+    }
+}
+
+/// Pads right comments of consecutive nodes to a common column.
+///
+/// Blocks are separated by blank lines. Bodies wider than `max_column` keep a single space before
+/// their comment and don't push the column of the rest of the block.
+pub fn align_right_comments(nodes: &mut [RenderedNode], max_column: usize) {
+    for block in nodes.split_mut(RenderedNode::is_blank) {
+        let column = block
+            .iter()
+            .filter(|node| node.right.is_some())
+            .map(RenderedNode::body_width)
+            .filter(|width| *width <= max_column)
+            .max();
+
+        let Some(column) = column else {
+            continue;
+        };
+
+        block
+            .iter_mut()
+            .filter(|node| node.right.is_some() && node.body_width() <= column)
+            .for_each(|node| node.right_column = Some(column));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{docstr, util::DocStr};
+
+    use super::{align_right_comments, RenderedNode};
+
+    fn node(body: &str, right: Option<&str>) -> RenderedNode {
+        RenderedNode {
+            top: None,
+            body: docstr!(body),
+            right: right.map(DocStr::from),
+            right_column: None,
+        }
+    }
+
+    fn render(nodes: Vec<RenderedNode>) -> String {
+        nodes
+            .into_iter()
+            .map(RenderedNode::into_doc_str)
+            .reduce(DocStr::merge)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn aligns_blocks_separated_by_blank_lines() {
+        let mut nodes = vec![
+            node("a = 1", Some("# one")),
+            node("long_key = 2", Some("# two")),
+            node("no_comment_but_long = 3", None),
+            node("", None),
+            node("b = 4", Some("# four")),
+        ];
+        align_right_comments(&mut nodes, 60);
+
+        assert_eq!(
+            render(nodes),
+            "a = 1        # one\nlong_key = 2 # two\nno_comment_but_long = 3\n\nb = 4 # four"
+        );
+    }
+
+    #[test]
+    fn long_lines_fall_back_to_single_space() {
+        let mut nodes = vec![
+            node("a = 1", Some("# one")),
+            node("very_long_key_name = 2", Some("# two")),
+            node("bb = 3", Some("# three")),
+        ];
+        align_right_comments(&mut nodes, 10);
+
+        assert_eq!(
+            render(nodes),
+            "a = 1  # one\nvery_long_key_name = 2 # two\nbb = 3 # three"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io,
+};
+
+use crate::node::key::Key;
+
+/// Problem with a node that only shows up in some formats, e.g. a value they can't represent.
+#[derive(Debug, Clone)]
+pub struct Lint {
+    pub severity: Severity,
+    /// Full key of the node, if it has one.
+    pub key: Option<Key>,
+    pub message: String,
+}
+
+impl Lint {
+    pub fn warning(key: Option<Key>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key,
+            message: message.into(),
+        }
+    }
+
+    pub fn error(key: Option<Key>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            key,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{}: {key}: {}", self.severity, self.message),
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Output is valid, but may not be read back as intended.
+    Warning,
+    /// Output can't be read back.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Errors that keep examples from being generated, along with the example name.
+#[derive(Debug, Clone)]
+pub struct LintErrors(pub Vec<(String, Lint)>);
+
+impl Display for LintErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, lint)) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{name}: {lint}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for LintErrors {}
+
+impl From<LintErrors> for io::Error {
+    fn from(value: LintErrors) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, value)
+    }
+}
@@ -0,0 +1,185 @@
+use crate::util::Indent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// `None` falls back to the formatter's default indent.
+    pub indent: Option<Indent>,
+    pub comment_style: CommentStyle,
+    pub quote_style: QuoteStyle,
+    pub trailing_newline: bool,
+    pub line_ending: LineEnding,
+    /// Trim whitespace left at line ends by padding and indentation of empty lines.
+    pub trim_trailing_whitespace: bool,
+    /// Collapse consecutive blank lines, e.g. from adjacent empty nodes, into one.
+    pub collapse_blank_lines: bool,
+    /// Render comments as rustdoc markdown, see [`render_plain`](crate::util::markdown::render_plain).
+    pub render_markdown: bool,
+    pub link_style: LinkStyle,
+    /// Comments are wrapped to fit, `None` leaves them as is.
+    pub max_width: Option<usize>,
+    /// Pad names of sibling named nodes so their separators line up.
+    pub align_keys: bool,
+    pub align_right_comments: bool,
+    /// Bodies wider than this keep a single space before their right comment.
+    pub right_comment_max_column: usize,
+    /// Set while formatting the items of a flow collection, e.g. a YAML `[a, b]`.
+    pub(crate) flow: bool,
+}
+
+impl FormatOptions {
+    pub fn with_indent(mut self, indent: Indent) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    pub fn with_comment_style(mut self, comment_style: CommentStyle) -> Self {
+        self.comment_style = comment_style;
+        self
+    }
+
+    pub fn with_quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    pub fn with_trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    pub fn with_trim_trailing_whitespace(mut self, trim_trailing_whitespace: bool) -> Self {
+        self.trim_trailing_whitespace = trim_trailing_whitespace;
+        self
+    }
+
+    pub fn with_collapse_blank_lines(mut self, collapse_blank_lines: bool) -> Self {
+        self.collapse_blank_lines = collapse_blank_lines;
+        self
+    }
+
+    pub fn with_render_markdown(mut self, render_markdown: bool) -> Self {
+        self.render_markdown = render_markdown;
+        self
+    }
+
+    pub fn with_link_style(mut self, link_style: LinkStyle) -> Self {
+        self.link_style = link_style;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_align_keys(mut self, align_keys: bool) -> Self {
+        self.align_keys = align_keys;
+        self
+    }
+
+    pub fn with_align_right_comments(mut self, align_right_comments: bool) -> Self {
+        self.align_right_comments = align_right_comments;
+        self
+    }
+
+    pub fn with_right_comment_max_column(mut self, right_comment_max_column: usize) -> Self {
+        self.right_comment_max_column = right_comment_max_column;
+        self
+    }
+
+    /// Options for content laid out on its own and then indented by `width` columns, e.g. the
+    /// nodes of a table.
+    pub(crate) fn nested(mut self, width: usize) -> Self {
+        self.max_width = self
+            .max_width
+            .map(|max_width| max_width.saturating_sub(width));
+        self
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent: None,
+            comment_style: Default::default(),
+            quote_style: Default::default(),
+            trailing_newline: true,
+            line_ending: Default::default(),
+            trim_trailing_whitespace: true,
+            collapse_blank_lines: false,
+            render_markdown: false,
+            link_style: Default::default(),
+            max_width: None,
+            align_keys: false,
+            align_right_comments: true,
+            right_comment_max_column: 60,
+            flow: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// `# comment`
+    #[default]
+    Spaced,
+    /// `#comment`
+    Compact,
+}
+
+impl CommentStyle {
+    pub fn prefix(&self, marker: &str, comment: &str) -> String {
+        match comment.is_empty() {
+            true => marker.to_string(),
+            false => format!("{}{comment}", self.line_start(marker)),
+        }
+    }
+
+    /// Text non-empty comment lines start with.
+    pub fn line_start(&self, marker: &str) -> String {
+        match self {
+            Self::Spaced => format!("{marker} "),
+            Self::Compact => marker.to_string(),
+        }
+    }
+}
+
+/// Preferred quotes for keys and strings, formatters fall back to double quotes when the content
+/// can't be represented with single ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteStyle {
+    #[default]
+    Double,
+    Single,
+}
+
+/// How markdown links are rendered in comments.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LinkStyle {
+    /// Keep only the link text.
+    #[default]
+    Strip,
+    /// Keep the link text and list URLs as numbered footnotes after the comment.
+    Footnotes,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
@@ -1,13 +1,645 @@
-use crate::{node::CommentNode, util::DocStr};
+use crate::{
+    node::types::number::{NumberSyntax, Radix},
+    util::{double_quoted, Indent},
+};
 
-use super::NodeFormatter;
+use super::{
+    options::{FormatOptions, QuoteStyle},
+    NodeFormatter,
+};
 
 pub struct TomlNodeFormatter {}
 
 impl NodeFormatter for TomlNodeFormatter {
+    const EXTENSION: &'static str = "toml";
+    const DEFAULT_INDENT: Indent = Indent::Spaces(4);
     const NAMED_NODE_SEPARATOR: &'static str = " = ";
+    const COMMENT_MARKER: &'static str = "#";
+    const DOCUMENT_SEPARATOR: Option<&'static str> = None;
+    const NUMBER_SYNTAX: NumberSyntax = NumberSyntax {
+        radixes: &[Radix::Binary, Radix::Octal, Radix::Hexadecimal],
+        digit_separator: Some('_'),
+        min_integer: i64::MIN as i128,
+        max_integer: i64::MAX as u128,
+        inf: "inf",
+        nan: "nan",
+    };
 
-    fn format_comment(CommentNode(comment): CommentNode) -> DocStr {
-        comment.map(|s| format!("# {s}"))
+    fn format_string(value: &str, options: &FormatOptions) -> String {
+        // Literal strings can't contain single quotes or control characters other than tab
+        let is_literal =
+            !value.contains('\'') && !value.chars().any(|c| c.is_control() && c != '\t');
+
+        match (options.quote_style, is_literal) {
+            (QuoteStyle::Single, true) => format!("'{value}'"),
+            _ => double_quoted(value),
+        }
+    }
+
+    fn format_path_string(path: &str, literal: bool, options: &FormatOptions) -> String {
+        // Escaped Windows paths are hard to read and easy to get wrong by hand
+        let quote_style = match literal || path.contains('\\') {
+            true => QuoteStyle::Single,
+            false => options.quote_style,
+        };
+
+        Self::format_string(path, &options.with_quote_style(quote_style))
+    }
+
+    fn format_key_segment(segment: &str, options: &FormatOptions) -> String {
+        let is_bare = !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        match is_bare {
+            true => segment.to_string(),
+            false => Self::format_string(segment, options),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use macros::node;
+
+    use std::time::Duration;
+
+    use crate::{
+        array, byte_size, choice, duration, empty, float, integer, key,
+        node::{
+            formatter::{
+                options::{FormatOptions, QuoteStyle},
+                NodeFormatter,
+            },
+            key::Key,
+            types::{
+                byte_size::{ByteSizeStyle, ByteUnit},
+                choice::ChoiceSchema,
+                duration::{DurationStyle, DurationUnit},
+                number::{FloatNotation, NumberNode, NumberSyntax, Radix},
+                tagged::{TaggedNode, TaggedVariant, Tagging},
+            },
+            Node,
+        },
+        optional, path, raw, table,
+        util::Indent,
+    };
+
+    use super::TomlNodeFormatter;
+
+    fn format_named_int(key: Key) -> String {
+        TomlNodeFormatter::format_node(node!(integer!(1).with_name(key)), &Default::default())
+            .to_string()
+    }
+
+    #[test]
+    fn bare_and_quoted_keys() {
+        assert_eq!(format_named_int(key!("bare_key-1")), "bare_key-1 = 1");
+        assert_eq!(format_named_int(key!("1234")), "1234 = 1");
+        assert_eq!(format_named_int(key!("my key")), "\"my key\" = 1");
+        assert_eq!(format_named_int(key!("a.b")), "\"a.b\" = 1");
+        assert_eq!(format_named_int(key!("ключ")), "\"ключ\" = 1");
+        assert_eq!(format_named_int(key!("")), "\"\" = 1");
+        assert_eq!(format_named_int(key!("q\"t")), "\"q\\\"t\" = 1");
+    }
+
+    #[test]
+    fn commented_out_node() {
+        let node = node!(
+            integer!(30).with_name("timeout"),
+            commented = true,
+            comments = { top: "Request timeout", right: "seconds" }
+        );
+
+        assert_eq!(
+            TomlNodeFormatter::format_node(node, &Default::default()).to_string(),
+            "# Request timeout\n# timeout = 30 # seconds"
+        );
+    }
+
+    #[test]
+    fn commented_out_node_uses_comment_prefix() {
+        struct IniFormatter;
+
+        impl NodeFormatter for IniFormatter {
+            const EXTENSION: &'static str = "ini";
+            const DEFAULT_INDENT: Indent = TomlNodeFormatter::DEFAULT_INDENT;
+            const NAMED_NODE_SEPARATOR: &'static str = TomlNodeFormatter::NAMED_NODE_SEPARATOR;
+            const COMMENT_MARKER: &'static str = ";";
+            const NUMBER_SYNTAX: NumberSyntax = TomlNodeFormatter::NUMBER_SYNTAX;
+            const DOCUMENT_SEPARATOR: Option<&'static str> = None;
+
+            fn comment_prefix(_options: &FormatOptions) -> String {
+                ";; ".to_string()
+            }
+
+            fn format_string(value: &str, options: &FormatOptions) -> String {
+                TomlNodeFormatter::format_string(value, options)
+            }
+
+            fn format_key_segment(segment: &str, options: &FormatOptions) -> String {
+                TomlNodeFormatter::format_key_segment(segment, options)
+            }
+        }
+
+        let node = node!(
+            array![integer!(1), integer!(2)].with_name("ports"),
+            commented = true,
+            comments = { top: "Listen on", right: "Defaults" }
+        );
+
+        assert_eq!(
+            IniFormatter::format_node(node, &FormatOptions::default().with_max_width(10))
+                .into_lines(),
+            [
+                ";; Listen",
+                ";; on",
+                ";; ports = [ ;; Defaults",
+                ";;     1,",
+                ";;     2,",
+                ";; ]",
+            ]
+        );
+    }
+
+    #[test]
+    fn single_quoted_keys() {
+        let options = FormatOptions::default().with_quote_style(QuoteStyle::Single);
+        let format = |key: Key| {
+            TomlNodeFormatter::format_node(node!(integer!(1).with_name(key)), &options).to_string()
+        };
+
+        assert_eq!(format(key!("my key")), "'my key' = 1");
+        assert_eq!(format(key!("it's")), "\"it's\" = 1");
+    }
+
+    #[test]
+    fn aligned_keys_and_comments() {
+        let nodes = [
+            node!(integer!(1).with_name("short"), comments = { right: "one" }),
+            node!(integer!(2).with_name("longer_key")),
+            node!(integer!(3).with_name("mid"), tabs = 1, comments = { right: "three" }),
+            node!(empty!()),
+            node!(integer!(4).with_name("x")),
+        ];
+        let options = FormatOptions::default().with_align_keys(true);
+
+        assert_eq!(
+            TomlNodeFormatter::format_block(nodes, &options)
+                .unwrap()
+                .to_string(),
+            "short      = 1 # one\nlonger_key = 2\n    mid    = 3 # three\n\nx = 4"
+        );
+    }
+
+    #[test]
+    fn dotted_keys() {
+        assert_eq!(format_named_int(key!("a", "b", "c")), "a.b.c = 1");
+        assert_eq!(format_named_int(key!("a", "b c")), "a.\"b c\" = 1");
+    }
+
+    #[test]
+    fn choice_lists_variants() {
+        let node = node!(
+            choice!("info", ["debug", "info", "warn"]).with_name("log_level"),
+            comments = { top: "Verbosity" }
+        );
+
+        assert_eq!(
+            TomlNodeFormatter::format_node(node, &Default::default()).to_string(),
+            "# Verbosity\nlog_level = \"info\" # Possible values: debug, info, warn"
+        );
+    }
+
+    /// Verbosity of the logs
+    #[allow(dead_code)]
+    #[derive(ChoiceSchema, Default)]
+    #[serde(rename_all = "kebab-case")]
+    enum LogLevel {
+        Trace,
+        Debug,
+        /// Default level
+        #[default]
+        Info,
+        /// Something might be wrong,
+        /// but keep going
+        #[serde(rename = "warning")]
+        Warn,
+        #[serde(rename(serialize = "err", deserialize = "error"))]
+        ErrorOnly,
+        #[serde(skip)]
+        Internal,
+        #[serde(skip_deserializing)]
+        Unreadable,
+    }
+
+    #[allow(dead_code)]
+    #[derive(ChoiceSchema)]
+    #[serde(rename_all(deserialize = "camelCase"))]
+    enum Greeting {
+        Ärger,
+        GutenTag,
+    }
+
+    #[test]
+    fn derived_choice_field() {
+        let node = node!(LogLevel::choice().with_name("log_level"));
+
+        assert_eq!(
+            TomlNodeFormatter::format_node(node, &Default::default()).into_lines(),
+            [
+                "# Possible values:",
+                "# - trace",
+                "# - debug",
+                "# - info: Default level",
+                "# - warning: Something might be wrong,",
+                "#   but keep going",
+                "# - error",
+                "log_level = \"info\"",
+            ]
+        );
+        assert_eq!(
+            TomlNodeFormatter::format_node(node!(Greeting::choice()), &Default::default())
+                .to_string(),
+            "\"Ärger\" # Possible values: Ärger, gutenTag"
+        );
+    }
+
+    #[test]
+    fn tagged_variants() {
+        let variants = || {
+            [
+                TaggedVariant::new("fs").add_node(node!(integer!(1).with_name("depth"))),
+                TaggedVariant::new("s3")
+                    .with_doc("Remote storage")
+                    .add_node(node!(integer!(9000).with_name("port"))),
+                TaggedVariant::new("memory"),
+            ]
+        };
+        let format = |tagging| {
+            let node = Node::new(
+                TaggedNode::new(tagging, variants())
+                    .with_name("storage")
+                    .with_selected("s3"),
+            );
+
+            TomlNodeFormatter::format_node(node, &Default::default()).into_lines()
+        };
+
+        assert_eq!(
+            format(Tagging::Internal { tag: "type".into() }),
+            [
+                "# Remote storage",
+                "storage.type = \"s3\"",
+                "storage.port = 9000",
+                "# storage.type = \"fs\"",
+                "# storage.depth = 1",
+                "# storage.type = \"memory\"",
+            ]
+        );
+        assert_eq!(
+            format(Tagging::External),
+            [
+                "# Remote storage",
+                "storage.s3.port = 9000",
+                "# storage.fs.depth = 1",
+                "# storage = \"memory\"",
+            ]
+        );
+        assert_eq!(
+            format(Tagging::Untagged),
+            [
+                "# Remote storage",
+                "storage.port = 9000",
+                "# storage.depth = 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn number_styles() {
+        let format = |number: NumberNode| {
+            TomlNodeFormatter::format_node(node!(number), &Default::default()).to_string()
+        };
+
+        assert_eq!(format(float!(4.0)), "4.0");
+        assert_eq!(format(float!(-0.5)), "-0.5");
+        assert_eq!(format(float!(1e20)), "1.0e20");
+        assert_eq!(
+            format(float!(1e20).with_notation(FloatNotation::Decimal)),
+            "100000000000000000000.0"
+        );
+        assert_eq!(
+            format(float!(1500.0).with_notation(FloatNotation::Scientific)),
+            "1.5e3"
+        );
+        assert_eq!(
+            format(float!(1234567.25).with_digit_grouping(3)),
+            "1_234_567.25"
+        );
+        assert_eq!(format(float!(f64::NEG_INFINITY)), "-inf");
+        assert_eq!(format(float!(f64::NAN)), "nan");
+        assert_eq!(
+            format(integer!(-1000000).with_digit_grouping(3)),
+            "-1_000_000"
+        );
+        assert_eq!(format(integer!(255).with_radix(Radix::Hexadecimal)), "0xFF");
+        assert_eq!(format(integer!(0o755).with_radix(Radix::Octal)), "0o755");
+        assert_eq!(
+            format(
+                integer!(0b1010_0101)
+                    .with_radix(Radix::Binary)
+                    .with_digit_grouping(4)
+            ),
+            "0b1010_0101"
+        );
+        // Prefixed integers can't be negative
+        assert_eq!(
+            format(integer!(-255).with_radix(Radix::Hexadecimal)),
+            "-255"
+        );
+    }
+
+    #[test]
+    fn bounds_comment() {
+        let nodes = [
+            node!(integer!(50)
+                .with_name("volume")
+                .with_range(0, 100)
+                .with_unit("%")),
+            node!(
+                integer!(0x10).with_name("mask").with_max(0xFF).with_radix(Radix::Hexadecimal),
+                comments = { right: "Bit mask" }
+            ),
+            node!(float!(0.5).with_name("ratio").with_step(0.25)),
+            node!(integer!(3000).with_name("timeout").with_unit("ms")),
+        ];
+
+        assert_eq!(
+            TomlNodeFormatter::format_block(nodes, &Default::default())
+                .unwrap()
+                .into_lines(),
+            [
+                "volume = 50    # range: 0–100 (%)",
+                "# max: 0xFF",
+                "mask = 0x10    # Bit mask",
+                "ratio = 0.5    # step: 0.25",
+                "timeout = 3000 # unit: ms",
+            ]
+        );
+    }
+
+    #[test]
+    fn lints_values_outside_bounds() {
+        let nodes = [
+            node!(integer!(150).with_name("percent").with_range(0, 100)),
+            node!(integer!(7).with_name("even").with_min(0).with_step(2)),
+            node!(float!(0.75)
+                .with_name("ratio")
+                .with_range(0.0, 1.0)
+                .with_step(0.25)),
+            node!(duration!(Duration::from_millis(1500))
+                .with_name("timeout")
+                .with_style(DurationStyle::Integer(DurationUnit::Seconds))),
+        ];
+
+        assert_eq!(
+            TomlNodeFormatter::lint(&nodes)
+                .into_iter()
+                .map(|lint| lint.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "warning: percent: 150 is above the maximum of 100",
+                "warning: even: 7 is not on a step of 2",
+                "warning: timeout: 1.5s is not a whole number of seconds",
+            ]
+        );
+    }
+
+    #[test]
+    fn lints_integers_out_of_range() {
+        let nodes = [
+            node!(integer!(u64::MAX).with_name("seed")),
+            node!(table!(
+                "limits",
+                [node!(integer!(i128::MIN).with_name("min"))]
+            )),
+            node!(integer!(i64::MAX).with_name("max")),
+            node!(byte_size!(u64::MAX)
+                .with_name("cache")
+                .with_style(ByteSizeStyle::Integer(ByteUnit::B))),
+            node!(byte_size!(u64::MAX)
+                .with_name("cache_mb")
+                .with_style(ByteSizeStyle::Integer(ByteUnit::MB))),
+            node!(duration!(Duration::MAX)
+                .with_name("timeout")
+                .with_style(DurationStyle::Integer(DurationUnit::Nanoseconds))),
+        ];
+
+        assert_eq!(
+            TomlNodeFormatter::lint(&nodes)
+                .into_iter()
+                .map(|lint| lint.to_string())
+                .collect::<Vec<_>>(),
+            [
+                "error: seed: 18446744073709551615 is out of the toml integer range",
+                "error: limits.min: -170141183460469231731687303715884105728 is out of the toml integer range",
+                "error: cache: 18446744073709551615 is out of the toml integer range",
+                "warning: cache_mb: 18446744073709551615 bytes is not a whole number of MB",
+                "error: timeout: 18446744073709551615999999999 is out of the toml integer range",
+            ]
+        );
+    }
+
+    #[test]
+    fn duration_and_byte_size_styles() {
+        let format = |node| {
+            TomlNodeFormatter::format_node(node, &Default::default())
+                .into_lines()
+                .join("\n")
+        };
+        let timeout = || duration!(Duration::from_millis(5_400_500)).with_name("timeout");
+
+        assert_eq!(
+            format(node!(timeout())),
+            "timeout = \"1h 30m 500ms\" # Duration with units (ns, us, ms, s, m, h, d), e.g. \"1h 30m\""
+        );
+        assert_eq!(
+            format(node!(timeout().with_style(DurationStyle::Iso8601))),
+            "timeout = \"PT1H30M0.5S\" # ISO 8601 duration, e.g. \"PT1H30M\""
+        );
+        assert_eq!(
+            format(node!(
+                timeout().with_style(DurationStyle::Integer(DurationUnit::Seconds))
+            )),
+            "timeout = 5400 # Duration in seconds"
+        );
+        assert_eq!(
+            format(node!(
+                duration!(Duration::from_secs(2 * 86_400)).with_style(DurationStyle::Iso8601)
+            )),
+            "\"P2D\" # ISO 8601 duration, e.g. \"PT1H30M\""
+        );
+        assert_eq!(
+            format(node!(byte_size!(512 << 20).with_name("cache"), comments = { right: "Per worker" })),
+            "# Size with units (B, KiB, MiB, GiB, TiB), e.g. \"512MiB\"\ncache = \"512MiB\" # Per worker"
+        );
+        assert_eq!(
+            format(node!(
+                byte_size!(1_500_000).with_style(ByteSizeStyle::Decimal)
+            )),
+            "\"1500kB\" # Size with units (B, kB, MB, GB, TB), e.g. \"500MB\""
+        );
+        assert_eq!(
+            format(node!(
+                byte_size!(4096).with_style(ByteSizeStyle::Integer(ByteUnit::B))
+            )),
+            "4096 # Size in bytes"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let format = |node| {
+            TomlNodeFormatter::format_node(node, &Default::default())
+                .into_lines()
+                .join("\n")
+        };
+
+        assert_eq!(
+            format(node!(path!("~/.config/app").with_name("dir"))),
+            "dir = \"~/.config/app\""
+        );
+        assert_eq!(
+            format(node!(path!("${XDG_CONFIG_HOME}/app/$PROFILE.toml")
+                .with_name("config")
+                .with_expansion("XDG_CONFIG_HOME", "/etc/xdg")
+                .with_windows("%APPDATA%\\app\\$PROFILE.toml")
+                .with_expansion("APPDATA", "C:\\Users\\me\\AppData"))),
+            "config = \"/etc/xdg/app/$PROFILE.toml\" # On Windows: 'C:\\Users\\me\\AppData\\app\\$PROFILE.toml'"
+        );
+        // Literal strings can't contain `'`
+        assert_eq!(format(node!(path!("C:\\it's"))), "\"C:\\\\it's\"");
+        assert_eq!(
+            format(node!(path!("/srv/data").with_literal(true))),
+            "'/srv/data'"
+        );
+    }
+
+    #[test]
+    fn unset_optional_values() {
+        let nodes = [
+            node!(integer!(8080).with_name("port")),
+            node!(
+                optional!(integer!(30).with_name("timeout")),
+                comments = { right: "seconds" }
+            ),
+            node!(optional!(choice!("json", ["json", "text"]).with_name("log"))),
+        ];
+
+        assert_eq!(
+            TomlNodeFormatter::format_block(nodes, &FormatOptions::default().with_align_keys(true))
+                .unwrap()
+                .into_lines(),
+            [
+                "port = 8080",
+                "# timeout = 30 # seconds",
+                "# log = \"json\" # Possible values: json, text",
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_text_for_other_formats_is_left_out() {
+        let nodes = [node!(table!(
+            "defaults",
+            [node!(raw!("yaml" => "base: &base"))]
+        ))];
+
+        assert_eq!(
+            TomlNodeFormatter::lint(&nodes)
+                .into_iter()
+                .map(|lint| lint.to_string())
+                .collect::<Vec<_>>(),
+            ["warning: defaults: raw node has no toml text and is left out"]
+        );
+    }
+
+    #[test]
+    fn arrays_break_when_too_wide() {
+        let ports = || array![integer!(8080), integer!(8081), integer!(8082)].with_name("ports");
+        let format = |node, options: &FormatOptions| {
+            TomlNodeFormatter::format_node(node, options)
+                .into_lines()
+                .join("\n")
+        };
+
+        assert_eq!(
+            format(node!(ports()), &Default::default()),
+            "ports = [8080, 8081, 8082]"
+        );
+        assert_eq!(
+            format(
+                node!(ports(), tabs = 1, commented = true),
+                &FormatOptions::default().with_max_width(24)
+            ),
+            "    # ports = [\n    #     8080,\n    #     8081,\n    #     8082,\n    # ]"
+        );
+        assert_eq!(
+            format(node!(array![].with_name("empty")), &Default::default()),
+            "empty = []"
+        );
+
+        let nodes = [
+            node!(ports()),
+            node!(array![integer!(1)].with_name("a"), comments = { right: "One" }),
+        ];
+
+        assert_eq!(
+            TomlNodeFormatter::format_block(
+                nodes,
+                &FormatOptions::default()
+                    .with_align_keys(true)
+                    .with_max_width(20)
+            )
+            .unwrap()
+            .into_lines(),
+            [
+                "ports = [",
+                "    8080,",
+                "    8081,",
+                "    8082,",
+                "]",
+                "a     = [1] # One",
+            ]
+        );
+    }
+    #[test]
+    fn wrapped_markdown_keeps_code_blocks() {
+        let node = node!(
+            integer!(1).with_name("x"),
+            comments = {
+                top: [
+                    "Computed by **this** function for every request:",
+                    "```",
+                    "let   x = some_function(argument_one, argument_two);",
+                    "```"
+                ]
+            }
+        );
+        let options = FormatOptions::default()
+            .with_render_markdown(true)
+            .with_max_width(30);
+
+        assert_eq!(
+            TomlNodeFormatter::format_node(node, &options).into_lines(),
+            [
+                "# Computed by this function",
+                "# for every request:",
+                "#     let   x = some_function(argument_one, argument_two);",
+                "x = 1",
+            ]
+        );
     }
 }
@@ -1,13 +1,554 @@
-use crate::{node::CommentNode, util::DocStr};
+use crate::{
+    node::{
+        key::Key,
+        types::{
+            array::ArrayNode,
+            number::{NumberSyntax, Radix},
+            table::TableNode,
+        },
+    },
+    util::{doc::Doc, double_quoted, str_width, Indent},
+};
 
-use super::NodeFormatter;
+use super::{
+    options::{FormatOptions, QuoteStyle},
+    NodeFormatter,
+};
 
 pub struct YamlFormatter;
 
+impl YamlFormatter {
+    const INDICATORS: &'static str = "-?:,[]{}#&*!|>'\"%@`";
+    const RESERVED: [&'static str; 13] = [
+        "true", "false", "yes", "no", "on", "off", "y", "n", "null", "~", ".inf", ".nan", "<<",
+    ];
+
+    /// Whether the string can be written unquoted without being read as another type or syntax.
+    fn is_plain_scalar(str: &str) -> bool {
+        let mut chars = str.chars();
+        let (Some(first), second) = (chars.next(), chars.next()) else {
+            return false;
+        };
+        // Signs included, e.g. `+1` or `+.inf`
+        let looks_numeric = first.is_ascii_digit()
+            || (first == '.' && second.is_some_and(|c| c.is_ascii_digit()))
+            || (first == '+' && second.is_some_and(|c| c.is_ascii_digit() || c == '.'));
+
+        !Self::INDICATORS.contains(first)
+            && !looks_numeric
+            && str.trim() == str
+            && !str.contains(": ")
+            && !str.contains(" #")
+            && !str.ends_with(':')
+            && !str.chars().any(char::is_control)
+            && !Self::RESERVED.contains(&str.to_lowercase().as_str())
+    }
+
+    /// Whether the string can be written unquoted inside a flow collection, where `,[]{}` end or
+    /// open one anywhere in the value.
+    fn is_plain_flow_scalar(str: &str) -> bool {
+        Self::is_plain_scalar(str) && !str.contains([',', '[', ']', '{', '}'])
+    }
+
+    /// Padded last key segment followed by `rest`, which starts with the separator, nested under
+    /// the other segments.
+    fn key_then(key: Key, rest: Doc, options: &FormatOptions) -> Doc {
+        let segments = key.segments();
+        let (last, parents) = segments
+            .split_last()
+            .expect("Keys have at least one segment");
+
+        Self::nest_under(
+            parents,
+            Doc::text(Self::format_key_segment(last, options))
+                .padded(0)
+                .append(rest),
+            options,
+        )
+    }
+
+    /// Nests `doc` one level deeper per key segment, `a:\n  b:\n    doc`.
+    fn nest_under(segments: &[String], doc: Doc, options: &FormatOptions) -> Doc {
+        segments.iter().rev().fold(doc, |nested, segment| {
+            Doc::text(format!("{}:", Self::format_key_segment(segment, options)))
+                .append(Doc::hardline().append(nested).nest(1))
+        })
+    }
+}
+
 impl NodeFormatter for YamlFormatter {
+    const EXTENSION: &'static str = "yaml";
+    const DEFAULT_INDENT: Indent = Indent::Spaces(2);
     const NAMED_NODE_SEPARATOR: &'static str = ": ";
+    const COMMENT_MARKER: &'static str = "#";
+    const DOCUMENT_SEPARATOR: Option<&'static str> = Some("---");
+    // YAML 1.2 core schema
+    const NUMBER_SYNTAX: NumberSyntax = NumberSyntax {
+        radixes: &[Radix::Octal, Radix::Hexadecimal],
+        digit_separator: None,
+        // The spec doesn't limit integers
+        min_integer: i128::MIN,
+        max_integer: u128::MAX,
+        inf: ".inf",
+        nan: ".nan",
+    };
+
+    fn indent(options: &FormatOptions) -> Indent {
+        // YAML forbids tabs for indentation
+        match options.indent {
+            Some(Indent::Tabs) | None => Self::DEFAULT_INDENT,
+            Some(indent) => indent,
+        }
+    }
+
+    fn format_null(_options: &FormatOptions) -> Option<Doc> {
+        Some(Doc::text("null"))
+    }
+
+    fn format_string(value: &str, options: &FormatOptions) -> String {
+        let can_single_quote = !value.chars().any(char::is_control);
+
+        let is_plain = match options.flow {
+            true => Self::is_plain_flow_scalar(value),
+            false => Self::is_plain_scalar(value),
+        };
+
+        match (is_plain, options.quote_style) {
+            (true, _) => value.to_string(),
+            (false, QuoteStyle::Single) if can_single_quote => {
+                format!("'{}'", value.replace('\'', "''"))
+            }
+            _ => double_quoted(value),
+        }
+    }
+
+    fn format_key_segment(segment: &str, options: &FormatOptions) -> String {
+        Self::format_string(segment, options)
+    }
+
+    fn aligned_key_width(key: &Key, options: &FormatOptions) -> Option<usize> {
+        // Dotted keys are nested, so there is no `key: value` line to align
+        match key.is_dotted() {
+            true => None,
+            false => Some(str_width(&Self::format_key(key, options))),
+        }
+    }
+
+    fn format_table(
+        TableNode { name, nodes }: TableNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> Doc {
+        // Nested one indent per key segment
+        let nesting = str_width(&Self::indent(options).repeat(name.segments().len()));
+        let nodes = Self::format_block(nodes, &options.nested(indent_width + nesting))
+            .map_or(Doc::Nil, Doc::from);
+
+        Self::nest_under(name.segments(), nodes, options)
+    }
+
+    fn format_named(key: Key, value: Doc, options: &FormatOptions) -> Doc {
+        Self::key_then(
+            key,
+            Doc::text(Self::NAMED_NODE_SEPARATOR).append(value),
+            options,
+        )
+    }
+
+    /// Flow sequence when it fits, block sequence otherwise. Block sequences can't be nested in
+    /// flow ones, so arrays inside a flow sequence always stay flow.
+    fn format_array(
+        ArrayNode { items, name }: ArrayNode,
+        indent_width: usize,
+        options: &FormatOptions,
+    ) -> Doc {
+        let is_empty = items.is_empty();
+        let flow_options = FormatOptions {
+            flow: true,
+            ..*options
+        };
+        let flow = Doc::text("[")
+            .append(Doc::join(
+                items
+                    .iter()
+                    .map(|item| Self::format_type(item.clone(), indent_width, &flow_options)),
+                Doc::text(", "),
+            ))
+            .append("]");
+
+        if options.flow {
+            return flow;
+        }
+
+        let block = Doc::join(
+            items.into_iter().map(|item| {
+                Doc::text("- ").append(Self::format_type(item, indent_width, options).nest(1))
+            }),
+            Doc::line(),
+        );
+
+        match (name, is_empty) {
+            (None, true) => flow,
+            (None, false) => Doc::if_break(block, flow).group(),
+            (Some(name), true) => Self::format_named(name, flow, options),
+            (Some(name), false) => Self::key_then(
+                name,
+                Doc::if_break(
+                    Doc::text(":").append(Doc::line().append(block).nest(1)),
+                    Doc::text(Self::NAMED_NODE_SEPARATOR).append(flow),
+                )
+                .group(),
+                options,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use macros::node;
+
+    use crate::{
+        array, choice, docstr_multi, float, integer, key,
+        node::{
+            formatter::{options::FormatOptions, NodeFormatter},
+            key::Key,
+        },
+        node::{
+            types::{
+                choice::ChoiceVariant,
+                number::Radix,
+                tagged::{TaggedNode, TaggedVariant, Tagging},
+            },
+            Node,
+        },
+        optional, path, raw, string, table,
+        util::Indent,
+    };
+
+    use super::YamlFormatter;
+
+    fn format_named_int(key: Key) -> String {
+        YamlFormatter::format_node(node!(integer!(1).with_name(key)), &Default::default())
+            .to_string()
+    }
+
+    #[test]
+    fn plain_and_quoted_keys() {
+        assert_eq!(format_named_int(key!("plain key")), "plain key: 1");
+        assert_eq!(format_named_int(key!("ключ")), "ключ: 1");
+        assert_eq!(format_named_int(key!("1st")), "\"1st\": 1");
+        assert_eq!(format_named_int(key!("yes")), "\"yes\": 1");
+        assert_eq!(format_named_int(key!("a: b")), "\"a: b\": 1");
+        assert_eq!(format_named_int(key!("- item")), "\"- item\": 1");
+        assert_eq!(format_named_int(key!(" padded")), "\" padded\": 1");
+        assert_eq!(format_named_int(key!("")), "\"\": 1");
+        assert_eq!(format_named_int(key!("+1")), "\"+1\": 1");
+        assert_eq!(format_named_int(key!("+.inf")), "\"+.inf\": 1");
+        assert_eq!(format_named_int(key!("<<")), "\"<<\": 1");
+        assert_eq!(format_named_int(key!("+ plus")), "+ plus: 1");
+    }
+
+    #[test]
+    fn tab_indent_is_forced_to_spaces() {
+        let node = node!(integer!(1).with_name("nested"), tabs = 2);
+
+        assert_eq!(
+            YamlFormatter::format_node(node, &FormatOptions::default().with_indent(Indent::Tabs))
+                .to_string(),
+            "    nested: 1"
+        );
+    }
+
+    #[test]
+    fn aligns_non_ascii_keys_and_comments() {
+        let nodes = [
+            node!(integer!(1).with_name("名前"), comments = { right: "名前の長さ" }),
+            node!(integer!(2).with_name("größe"), comments = { right: "Größe" }),
+            node!(integer!(3).with_name("🦀"), comments = { right: "краб" }),
+            node!(integer!(4).with_name("cafe\u{301}"), comments = { right: "café" }),
+        ];
+        let options = FormatOptions::default().with_align_keys(true);
+
+        assert_eq!(
+            YamlFormatter::format_block(nodes, &options)
+                .unwrap()
+                .into_lines(),
+            [
+                "名前 : 1 # 名前の長さ",
+                "größe: 2 # Größe",
+                "🦀   : 3 # краб",
+                "cafe\u{301} : 4 # café",
+            ]
+        );
+    }
+
+    #[test]
+    fn dotted_keys_nest() {
+        assert_eq!(
+            format_named_int(key!("a", "b c", "d")),
+            "a:\n  b c:\n    d: 1"
+        );
+    }
+
+    #[test]
+    fn choice_quotes_ambiguous_values() {
+        let node = node!(
+            choice!("off", [ChoiceVariant::new("off").with_doc("Disabled"), "1.5"])
+                .with_name("mode"),
+            comments = { right: "Compatibility" }
+        );
+
+        assert_eq!(
+            YamlFormatter::format_node(node, &Default::default()).to_string(),
+            "# Possible values:\n# - off: Disabled\n# - 1.5\nmode: \"off\" # Compatibility"
+        );
+    }
+
+    #[test]
+    fn adjacently_tagged_variants_nest() {
+        let node = Node::new(
+            TaggedNode::new(
+                Tagging::Adjacent {
+                    tag: "type".into(),
+                    content: "with".into(),
+                },
+                [
+                    TaggedVariant::new("s3").with_fields([
+                        node!(integer!(9000).with_name("port"), comments = { right: "S3" }),
+                        node!(integer!(3).with_name("retries")),
+                    ]),
+                    TaggedVariant::new("fs").add_node(node!(integer!(1).with_name("depth"))),
+                ],
+            )
+            .with_name(key!("backend", "storage")),
+        )
+        .with_tabs(1);
+
+        assert_eq!(
+            YamlFormatter::format_node(node, &Default::default()).into_lines(),
+            [
+                "  backend:",
+                "    storage:",
+                "      type: s3",
+                "      with:",
+                "        port: 9000 # S3",
+                "        retries: 3",
+                "  # backend:",
+                "  #   storage:",
+                "  #     type: fs",
+                "  #     with:",
+                "  #       depth: 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn core_schema_numbers() {
+        let format = |node| YamlFormatter::format_node(node, &Default::default()).to_string();
+
+        assert_eq!(format(node!(float!(f64::INFINITY))), ".inf");
+        assert_eq!(format(node!(float!(f64::NAN))), ".nan");
+        assert_eq!(format(node!(float!(3.0))), "3.0");
+        assert_eq!(
+            format(node!(integer!(5)
+                .with_radix(Radix::Binary)
+                .with_digit_grouping(2))),
+            "5"
+        );
+        assert_eq!(format(node!(integer!(8).with_radix(Radix::Octal))), "0o10");
+    }
+
+    #[test]
+    fn integers_are_unbounded() {
+        let nodes = [
+            node!(integer!(u64::MAX).with_name("seed")),
+            node!(table!(
+                "limits",
+                [node!(integer!(i128::MIN).with_name("min"))]
+            )),
+        ];
+
+        assert!(YamlFormatter::lint(&nodes).is_empty());
+        assert_eq!(
+            YamlFormatter::format_block(nodes, &Default::default())
+                .unwrap()
+                .into_lines(),
+            [
+                "seed: 18446744073709551615",
+                "limits:",
+                "  min: -170141183460469231731687303715884105728",
+            ]
+        );
+    }
+
+    #[test]
+    fn unset_optional_values() {
+        let nodes = [
+            node!(integer!(8080).with_name("port")),
+            node!(
+                optional!(integer!(30).with_name("timeout")),
+                comments = { right: "seconds" }
+            ),
+            node!(optional!(choice!("json", ["json", "text"]).with_name("log"))),
+        ];
+
+        assert_eq!(
+            YamlFormatter::format_block(nodes, &FormatOptions::default().with_align_keys(true))
+                .unwrap()
+                .into_lines(),
+            [
+                "port   : 8080",
+                "timeout: null # seconds",
+                "log    : null # Possible values: json, text",
+            ]
+        );
+    }
+
+    #[test]
+    fn raw_text_is_verbatim() {
+        let nodes = [
+            node!(table!(
+                "defaults",
+                [node!(
+                    raw!("yaml" => docstr_multi!("base: &base", "  retries: 3")),
+                    comments = { top: "Shared settings" }
+                )]
+            )),
+            node!(integer!(1).with_name("version")),
+        ];
+
+        assert!(YamlFormatter::lint(&nodes).is_empty());
+        assert_eq!(
+            YamlFormatter::format_block(nodes, &Default::default())
+                .unwrap()
+                .into_lines(),
+            [
+                "defaults:",
+                "  # Shared settings",
+                "  base: &base",
+                "    retries: 3",
+                "version: 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_are_plain_when_possible() {
+        let format = |node| YamlFormatter::format_node(node, &Default::default()).to_string();
+
+        assert_eq!(
+            format(node!(path!("~/data").with_expansion("~", "/home/me"))),
+            "/home/me/data"
+        );
+        assert_eq!(format(node!(path!("C:\\data"))), "C:\\data");
+        assert_eq!(format(node!(path!("%TEMP%\\x"))), "\"%TEMP%\\\\x\"");
+    }
+
+    #[test]
+    fn strings_are_plain_when_possible() {
+        let format = |node| YamlFormatter::format_node(node, &Default::default()).to_string();
+
+        assert_eq!(
+            format(node!(string!("example.com").with_name("host"))),
+            "host: example.com"
+        );
+        assert_eq!(format(node!(string!("yes"))), "\"yes\"");
+        assert_eq!(format(node!(string!("8080"))), "\"8080\"");
+    }
+
+    #[test]
+    fn flow_items_quote_flow_indicators() {
+        let xs = || array![string!("a, b"), string!("c]"), string!("{d}"), string!("e")];
+        let format = |node, options: &FormatOptions| {
+            YamlFormatter::format_node(node, options)
+                .into_lines()
+                .join("\n")
+        };
+
+        assert_eq!(
+            format(node!(xs().with_name("xs")), &Default::default()),
+            "xs: [\"a, b\", \"c]\", \"{d}\", e]"
+        );
+        assert_eq!(
+            format(node!(xs()), &FormatOptions::default().with_max_width(8)),
+            "- a, b\n- c]\n- \"{d}\"\n- e"
+        );
+    }
+
+    #[test]
+    fn nested_tables_fit_max_width() {
+        let format = |node, max_width| {
+            YamlFormatter::format_node(node, &FormatOptions::default().with_max_width(max_width))
+                .into_lines()
+        };
+        let db = node!(table!(
+            "db",
+            [node!(
+                integer!(5432).with_name("port"),
+                comments = { top: "Comment that should be wrapped" }
+            )]
+        ));
+        let ports = node!(table!(
+            key!("outer", "inner"),
+            [node!(array![
+                integer!(8080),
+                integer!(8081),
+                integer!(8082)
+            ]
+            .with_name("ports"))]
+        ));
+
+        assert_eq!(
+            format(db, 20),
+            [
+                "db:",
+                "  # Comment that",
+                "  # should be",
+                "  # wrapped",
+                "  port: 5432",
+            ]
+        );
+        assert_eq!(
+            format(ports, 28),
+            [
+                "outer:",
+                "  inner:",
+                "    ports:",
+                "      - 8080",
+                "      - 8081",
+                "      - 8082",
+            ]
+        );
+    }
+
+    #[test]
+    fn arrays_become_block_sequences() {
+        let ports = || array![integer!(8080), integer!(8081)].with_name(key!("server", "ports"));
+        let format = |node, options: &FormatOptions| {
+            YamlFormatter::format_node(node, options)
+                .into_lines()
+                .join("\n")
+        };
 
-    fn format_comment(CommentNode(comment): CommentNode) -> DocStr {
-        comment.map(|s| format!("# {s}"))
+        assert_eq!(
+            format(node!(ports()), &Default::default()),
+            "server:\n  ports: [8080, 8081]"
+        );
+        assert_eq!(
+            format(node!(ports()), &FormatOptions::default().with_max_width(16)),
+            "server:\n  ports:\n    - 8080\n    - 8081"
+        );
+        assert_eq!(
+            format(
+                node!(array![array![integer!(1), integer!(2)], integer!(3)]),
+                &FormatOptions::default().with_max_width(4)
+            ),
+            "- - 1\n  - 2\n- 3"
+        );
+        assert_eq!(
+            format(node!(array![].with_name("empty")), &Default::default()),
+            "empty: []"
+        );
     }
 }
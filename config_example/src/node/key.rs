@@ -0,0 +1,58 @@
+use std::fmt::{self, Display};
+
+/// Node name, either a single key or (opt-in via [`Key::dotted`]) a nested one like `a.b.c`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key(Vec<String>);
+
+impl Key {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(vec![name.into()])
+    }
+
+    pub fn dotted(segments: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let segments = segments.into_iter().map(Into::into).collect::<Vec<_>>();
+
+        match segments.is_empty() {
+            true => Self::new(""),
+            false => Self(segments),
+        }
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn is_dotted(&self) -> bool {
+        self.0.len() > 1
+    }
+
+    /// `self.other`
+    pub fn join(&self, other: &Key) -> Self {
+        Self(self.0.iter().chain(&other.0).cloned().collect())
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join("."))
+    }
+}
+
+impl<S> From<S> for Key
+where
+    S: Into<String>,
+{
+    fn from(value: S) -> Self {
+        Self::new(value)
+    }
+}
+
+#[macro_export]
+macro_rules! key {
+    ($name:expr) => {
+        $crate::node::key::Key::new($name)
+    };
+    ($($segment:expr),+) => {
+        $crate::node::key::Key::dotted([$($segment),+])
+    };
+}
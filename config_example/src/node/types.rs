@@ -1,16 +1,182 @@
+pub mod array;
+pub mod byte_size;
+pub mod choice;
 pub mod comment;
+pub mod duration;
 pub mod number;
+pub mod optional;
+pub mod path;
+pub mod placeholder;
+pub mod quantity;
+pub mod raw;
+pub mod secret;
+pub mod string;
+pub mod table;
+pub mod tagged;
 
 use derive_more::From;
 
-use self::{comment::CommentNode, number::NumberNode};
+use self::{
+    array::ArrayNode,
+    byte_size::ByteSizeNode,
+    choice::ChoiceNode,
+    comment::CommentNode,
+    duration::DurationNode,
+    number::NumberNode,
+    optional::OptionalNode,
+    path::PathNode,
+    placeholder::{PlaceholderNode, Variables},
+    raw::RawNode,
+    secret::SecretNode,
+    string::StringNode,
+    table::TableNode,
+    tagged::TaggedNode,
+};
 
-#[derive(From)]
+use super::key::Key;
+
+#[derive(Clone, From)]
 pub enum NodeType {
     Comment(CommentNode),
     Empty,
     EmptyMultiline(usize),
     Number(NumberNode),
+    String(StringNode),
+    Choice(ChoiceNode),
+    Table(TableNode),
+    Tagged(TaggedNode),
+    Optional(OptionalNode),
+    Duration(DurationNode),
+    ByteSize(ByteSizeNode),
+    Path(PathNode),
+    Secret(SecretNode),
+    Placeholder(PlaceholderNode),
+    Raw(RawNode),
+    Array(ArrayNode),
+}
+
+impl NodeType {
+    pub fn key(&self) -> Option<&Key> {
+        match self {
+            Self::Number(NumberNode { name, .. })
+            | Self::String(StringNode { name, .. })
+            | Self::Choice(ChoiceNode { name, .. })
+            | Self::Duration(DurationNode { name, .. })
+            | Self::ByteSize(ByteSizeNode { name, .. })
+            | Self::Path(PathNode { name, .. })
+            | Self::Secret(SecretNode { name, .. })
+            | Self::Placeholder(PlaceholderNode { name, .. })
+            | Self::Array(ArrayNode { name, .. }) => name.as_ref(),
+            Self::Table(TableNode { name, .. }) => Some(name),
+            Self::Tagged(TaggedNode { name, .. }) => name.as_ref(),
+            Self::Optional(OptionalNode { example, .. }) => example.key(),
+            Self::Comment(_) | Self::Empty | Self::EmptyMultiline(_) | Self::Raw(_) => None,
+        }
+    }
+
+    /// Nests the node under `prefix`, e.g. for dotted keys of a table's nodes.
+    pub fn prefix_key(&mut self, prefix: &Key) {
+        let name = match self {
+            Self::Number(NumberNode { name, .. })
+            | Self::String(StringNode { name, .. })
+            | Self::Choice(ChoiceNode { name, .. })
+            | Self::Tagged(TaggedNode { name, .. })
+            | Self::Duration(DurationNode { name, .. })
+            | Self::ByteSize(ByteSizeNode { name, .. })
+            | Self::Path(PathNode { name, .. })
+            | Self::Secret(SecretNode { name, .. })
+            | Self::Placeholder(PlaceholderNode { name, .. })
+            | Self::Array(ArrayNode { name, .. }) => name,
+            Self::Table(TableNode { name, .. }) => {
+                *name = prefix.join(name);
+                return;
+            }
+            Self::Optional(OptionalNode { example, .. }) => {
+                example.prefix_key(prefix);
+                return;
+            }
+            Self::Comment(_) | Self::Empty | Self::EmptyMultiline(_) | Self::Raw(_) => return,
+        };
+
+        *name = Some(match name.take() {
+            Some(name) => prefix.join(&name),
+            None => prefix.clone(),
+        });
+    }
+
+    /// Replaces bound placeholders with their values.
+    pub fn substitute(&mut self, variables: &Variables) {
+        match self {
+            Self::Placeholder(PlaceholderNode { variable, name, .. }) => {
+                if let Some(value) = variables.get(variable) {
+                    let mut value = value.clone();
+
+                    if let Some(name) = name {
+                        value.prefix_key(name);
+                    }
+
+                    *self = value;
+                }
+            }
+            Self::Table(TableNode { nodes, .. }) => {
+                for node in nodes {
+                    node.ty.substitute(variables);
+                }
+            }
+            Self::Tagged(TaggedNode { variants, .. }) => {
+                for node in variants.iter_mut().flat_map(|variant| &mut variant.fields) {
+                    node.ty.substitute(variables);
+                }
+            }
+            Self::Optional(OptionalNode { example, .. }) => example.substitute(variables),
+            Self::Array(ArrayNode { items, .. }) => {
+                for item in items {
+                    item.substitute(variables);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces values at any of the `secrets` keys with placeholders, keeping their comments.
+    pub fn redact(&mut self, parent: Option<&Key>, secrets: &[Key]) {
+        let key = match (parent, self.key()) {
+            (Some(parent), Some(key)) => Some(parent.join(key)),
+            (parent, key) => key.or(parent).cloned(),
+        };
+
+        match self {
+            Self::Table(TableNode { nodes, .. }) => {
+                for node in nodes {
+                    node.ty.redact(key.as_ref(), secrets);
+                }
+            }
+            Self::Tagged(TaggedNode {
+                tagging, variants, ..
+            }) => {
+                for variant in variants {
+                    let fields_key = match (&key, tagging.fields_key(&variant.tag)) {
+                        (Some(key), Some(fields_key)) => Some(key.join(&fields_key)),
+                        (key, fields_key) => fields_key.or(key.clone()),
+                    };
+
+                    for node in &mut variant.fields {
+                        node.ty.redact(fields_key.as_ref(), secrets);
+                    }
+                }
+            }
+            Self::Optional(OptionalNode { example, .. }) => example.redact(parent, secrets),
+            // Neither holds a real value
+            Self::Secret(_) | Self::Placeholder(_) => {}
+            _ => {
+                if let (Some(full_key), Some(name)) = (key, self.key()) {
+                    if secrets.contains(&full_key) {
+                        *self = Self::Secret(SecretNode::named(name.clone()));
+                    }
+                }
+            }
+        }
+    }
 }
 
 #[macro_export]
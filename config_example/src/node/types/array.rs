@@ -0,0 +1,36 @@
+use crate::node::key::Key;
+
+use super::NodeType;
+
+/// List of values, laid out on one line when it fits in the width and one item per line otherwise.
+#[derive(Clone, Default)]
+pub struct ArrayNode {
+    pub items: Vec<NodeType>,
+    pub name: Option<Key>,
+}
+
+impl ArrayNode {
+    pub fn new(items: impl IntoIterator<Item = impl Into<NodeType>>) -> Self {
+        Self {
+            items: items.into_iter().map(Into::into).collect(),
+            name: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn add_item(mut self, item: impl Into<NodeType>) -> Self {
+        self.items.push(item.into());
+        self
+    }
+}
+
+#[macro_export]
+macro_rules! array {
+    [$($item:expr),* $(,)?] => {
+        $crate::node::types::array::ArrayNode::default()$(.add_item($item))*
+    };
+}
@@ -0,0 +1,153 @@
+use crate::{docstr, node::key::Key, util::DocStr};
+
+use super::quantity::Quantity;
+
+#[derive(Clone)]
+pub struct ByteSizeNode {
+    pub bytes: u64,
+    pub name: Option<Key>,
+    pub style: ByteSizeStyle,
+}
+
+impl ByteSizeNode {
+    pub fn new(bytes: u64) -> Self {
+        Self {
+            bytes,
+            name: None,
+            style: Default::default(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_style(mut self, style: ByteSizeStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Quantity for ByteSizeNode {
+    fn name(&self) -> Option<&Key> {
+        self.name.as_ref()
+    }
+
+    fn text(&self) -> String {
+        let bytes = self.bytes;
+        let exact = |units: &[ByteUnit]| {
+            let unit = units
+                .iter()
+                .find(|unit| bytes.is_multiple_of(unit.bytes()))
+                .copied()
+                .unwrap_or(ByteUnit::B);
+
+            format!("{}{}", bytes / unit.bytes(), unit.symbol())
+        };
+
+        match self.style {
+            ByteSizeStyle::Binary if bytes > 0 => exact(&ByteUnit::BINARY),
+            ByteSizeStyle::Decimal if bytes > 0 => exact(&ByteUnit::DECIMAL),
+            ByteSizeStyle::Binary | ByteSizeStyle::Decimal => "0B".to_string(),
+            ByteSizeStyle::Integer(unit) => (bytes / unit.bytes()).to_string(),
+        }
+    }
+
+    fn amount(&self) -> Option<u128> {
+        match self.style {
+            ByteSizeStyle::Integer(unit) => Some((self.bytes / unit.bytes()).into()),
+            ByteSizeStyle::Binary | ByteSizeStyle::Decimal => None,
+        }
+    }
+
+    fn describe_syntax(&self) -> DocStr {
+        match self.style {
+            ByteSizeStyle::Binary => {
+                docstr!("Size with units (B, KiB, MiB, GiB, TiB), e.g. \"512MiB\"")
+            }
+            ByteSizeStyle::Decimal => {
+                docstr!("Size with units (B, kB, MB, GB, TB), e.g. \"500MB\"")
+            }
+            ByteSizeStyle::Integer(ByteUnit::B) => docstr!("Size in bytes"),
+            ByteSizeStyle::Integer(unit) => docstr!(format!("Size in {}", unit.symbol())),
+        }
+    }
+
+    fn truncation(&self) -> Option<String> {
+        match self.style {
+            ByteSizeStyle::Integer(unit) if !self.bytes.is_multiple_of(unit.bytes()) => {
+                Some(format!(
+                    "{} bytes is not a whole number of {}",
+                    self.bytes,
+                    unit.symbol()
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ByteSizeStyle {
+    /// `"512MiB"`, in the largest power of 1024 that divides the size.
+    #[default]
+    Binary,
+    /// `"500MB"`, in the largest power of 1000 that divides the size.
+    Decimal,
+    /// `524288` of the unit, truncated.
+    Integer(ByteUnit),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteUnit {
+    B,
+    KB,
+    KiB,
+    MB,
+    MiB,
+    GB,
+    GiB,
+    TB,
+    TiB,
+}
+
+impl ByteUnit {
+    const BINARY: [Self; 5] = [Self::TiB, Self::GiB, Self::MiB, Self::KiB, Self::B];
+    const DECIMAL: [Self; 5] = [Self::TB, Self::GB, Self::MB, Self::KB, Self::B];
+
+    pub fn bytes(&self) -> u64 {
+        match self {
+            Self::B => 1,
+            Self::KB => 1_000,
+            Self::KiB => 1 << 10,
+            Self::MB => 1_000_000,
+            Self::MiB => 1 << 20,
+            Self::GB => 1_000_000_000,
+            Self::GiB => 1 << 30,
+            Self::TB => 1_000_000_000_000,
+            Self::TiB => 1 << 40,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::B => "B",
+            Self::KB => "kB",
+            Self::KiB => "KiB",
+            Self::MB => "MB",
+            Self::MiB => "MiB",
+            Self::GB => "GB",
+            Self::GiB => "GiB",
+            Self::TB => "TB",
+            Self::TiB => "TiB",
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! byte_size {
+    ($bytes:expr) => {
+        $crate::node::types::byte_size::ByteSizeNode::new($bytes)
+    };
+}
@@ -0,0 +1,115 @@
+use crate::{docstr, docstr_multi_iter, node::key::Key, util::DocStr};
+
+pub use macros::ChoiceSchema;
+
+/// Value picked from a fixed set of variants, e.g. a unit-variant enum.
+#[derive(Clone)]
+pub struct ChoiceNode {
+    pub selected: String,
+    pub variants: Vec<ChoiceVariant>,
+    pub name: Option<Key>,
+}
+
+impl ChoiceNode {
+    pub fn new(
+        selected: impl Into<String>,
+        variants: impl IntoIterator<Item = impl Into<ChoiceVariant>>,
+    ) -> Self {
+        Self {
+            selected: selected.into(),
+            variants: variants.into_iter().map(Into::into).collect(),
+            name: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Lists the variants, on one line if none of them is documented.
+    pub fn describe_variants(&self) -> Option<DocStr> {
+        if self.variants.is_empty() {
+            return None;
+        }
+
+        match self.variants.iter().any(|variant| variant.doc.is_some()) {
+            true => Some(docstr!("Possible values:").merge(docstr_multi_iter!(
+                self.variants.iter().flat_map(ChoiceVariant::describe)
+            ))),
+            false => Some(docstr!(format!(
+                "Possible values: {}",
+                self.variants
+                    .iter()
+                    .map(|variant| variant.value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+}
+
+/// Types with a fixed set of values, e.g. unit-variant enums through the derive, which reads
+/// variant names, serde renames, doc comments and `#[default]`.
+pub trait ChoiceSchema {
+    /// Every variant, with the default or the first one selected.
+    fn choice() -> ChoiceNode;
+}
+
+#[derive(Clone)]
+pub struct ChoiceVariant {
+    pub value: String,
+    pub doc: Option<DocStr>,
+}
+
+impl ChoiceVariant {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            doc: None,
+        }
+    }
+
+    pub fn with_doc(mut self, doc: impl Into<DocStr>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut doc_lines = self
+            .doc
+            .clone()
+            .map(DocStr::into_lines)
+            .unwrap_or_default()
+            .into_iter();
+
+        let first = match doc_lines.next() {
+            Some(line) => format!("- {}: {line}", self.value),
+            None => format!("- {}", self.value),
+        };
+
+        [first]
+            .into_iter()
+            .chain(doc_lines.map(|line| format!("  {line}")))
+            .collect()
+    }
+}
+
+impl<S> From<S> for ChoiceVariant
+where
+    S: Into<String>,
+{
+    fn from(value: S) -> Self {
+        Self::new(value)
+    }
+}
+
+#[macro_export]
+macro_rules! choice {
+    ($selected:expr, [$($variant:expr),* $(,)?]) => {
+        $crate::node::types::choice::ChoiceNode::new(
+            $selected,
+            [$($crate::node::types::choice::ChoiceVariant::from($variant)),*],
+        )
+    };
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use crate::{docstr, node::key::Key, util::DocStr};
+
+use super::quantity::Quantity;
+
+#[derive(Clone)]
+pub struct DurationNode {
+    pub value: Duration,
+    pub name: Option<Key>,
+    pub style: DurationStyle,
+}
+
+impl DurationNode {
+    pub fn new(value: Duration) -> Self {
+        Self {
+            value,
+            name: None,
+            style: Default::default(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_style(mut self, style: DurationStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Quantity for DurationNode {
+    fn name(&self) -> Option<&Key> {
+        self.name.as_ref()
+    }
+
+    fn text(&self) -> String {
+        match self.style {
+            DurationStyle::Humantime => humantime(self.value),
+            DurationStyle::Iso8601 => iso8601(self.value),
+            DurationStyle::Integer(unit) => (self.value.as_nanos() / unit.nanos()).to_string(),
+        }
+    }
+
+    fn amount(&self) -> Option<u128> {
+        match self.style {
+            DurationStyle::Integer(unit) => Some(self.value.as_nanos() / unit.nanos()),
+            DurationStyle::Humantime | DurationStyle::Iso8601 => None,
+        }
+    }
+
+    fn describe_syntax(&self) -> DocStr {
+        match self.style {
+            DurationStyle::Humantime => {
+                docstr!("Duration with units (ns, us, ms, s, m, h, d), e.g. \"1h 30m\"")
+            }
+            DurationStyle::Iso8601 => docstr!("ISO 8601 duration, e.g. \"PT1H30M\""),
+            DurationStyle::Integer(unit) => docstr!(format!("Duration in {}", unit.name())),
+        }
+    }
+
+    fn truncation(&self) -> Option<String> {
+        match self.style {
+            DurationStyle::Integer(unit) if !self.value.as_nanos().is_multiple_of(unit.nanos()) => {
+                Some(format!(
+                    "{:?} is not a whole number of {}",
+                    self.value,
+                    unit.name()
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// `"1h 30m"`
+    #[default]
+    Humantime,
+    /// `"PT1H30M"`
+    Iso8601,
+    /// `5400` of the unit, truncated.
+    Integer(DurationUnit),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationUnit {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+}
+
+impl DurationUnit {
+    const ALL: [Self; 7] = [
+        Self::Days,
+        Self::Hours,
+        Self::Minutes,
+        Self::Seconds,
+        Self::Milliseconds,
+        Self::Microseconds,
+        Self::Nanoseconds,
+    ];
+
+    pub fn nanos(&self) -> u128 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+            Self::Minutes => 60 * 1_000_000_000,
+            Self::Hours => 60 * 60 * 1_000_000_000,
+            Self::Days => 24 * 60 * 60 * 1_000_000_000,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Nanoseconds => "ns",
+            Self::Microseconds => "us",
+            Self::Milliseconds => "ms",
+            Self::Seconds => "s",
+            Self::Minutes => "m",
+            Self::Hours => "h",
+            Self::Days => "d",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Nanoseconds => "nanoseconds",
+            Self::Microseconds => "microseconds",
+            Self::Milliseconds => "milliseconds",
+            Self::Seconds => "seconds",
+            Self::Minutes => "minutes",
+            Self::Hours => "hours",
+            Self::Days => "days",
+        }
+    }
+}
+
+fn humantime(value: Duration) -> String {
+    let mut nanos = value.as_nanos();
+    let mut parts = vec![];
+
+    for unit in DurationUnit::ALL {
+        let amount = nanos / unit.nanos();
+        nanos %= unit.nanos();
+
+        if amount > 0 {
+            parts.push(format!("{amount}{}", unit.symbol()));
+        }
+    }
+
+    match parts.is_empty() {
+        true => "0s".to_string(),
+        false => parts.join(" "),
+    }
+}
+
+fn iso8601(value: Duration) -> String {
+    let days = value.as_secs() / 86_400;
+    let hours = value.as_secs() % 86_400 / 3_600;
+    let minutes = value.as_secs() % 3_600 / 60;
+    let seconds = value.as_secs() % 60;
+    let nanos = value.subsec_nanos();
+
+    let mut res = "P".to_string();
+
+    if days > 0 {
+        res.push_str(&format!("{days}D"));
+    }
+
+    if hours > 0 || minutes > 0 || seconds > 0 || nanos > 0 || days == 0 {
+        res.push('T');
+    }
+
+    if hours > 0 {
+        res.push_str(&format!("{hours}H"));
+    }
+
+    if minutes > 0 {
+        res.push_str(&format!("{minutes}M"));
+    }
+
+    match (seconds, nanos) {
+        (_, 1..) => {
+            let fraction = format!("{nanos:09}");
+            res.push_str(&format!("{seconds}.{}S", fraction.trim_end_matches('0')));
+        }
+        (1.., 0) => res.push_str(&format!("{seconds}S")),
+        (0, 0) if res == "PT" => res.push_str("0S"),
+        (0, 0) => {}
+    }
+
+    res
+}
+
+#[macro_export]
+macro_rules! duration {
+    ($value:expr) => {
+        $crate::node::types::duration::DurationNode::new($value)
+    };
+}
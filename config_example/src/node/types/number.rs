@@ -1,13 +1,26 @@
-use derive_more::Display;
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+};
 
+use crate::{docstr, node::key::Key, util::DocStr};
+
+#[derive(Clone)]
 pub struct NumberNode {
     pub ty: NumberType,
-    pub name: Option<String>,
+    pub name: Option<Key>,
+    pub style: NumberStyle,
+    pub bounds: NumberBounds,
 }
 
 impl NumberNode {
     pub fn new(ty: NumberType) -> Self {
-        Self { ty, name: None }
+        Self {
+            ty,
+            name: None,
+            style: Default::default(),
+            bounds: Default::default(),
+        }
     }
 
     pub fn integer(int: impl IntoIntegerType) -> Self {
@@ -18,32 +31,376 @@ impl NumberNode {
         Self::new(NumberType::float(float))
     }
 
-    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
         self.name = Some(name.into());
         self
     }
+
+    pub fn with_style(mut self, style: NumberStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_radix(mut self, radix: Radix) -> Self {
+        self.style.radix = radix;
+        self
+    }
+
+    pub fn with_digit_grouping(mut self, group_size: usize) -> Self {
+        self.style.digit_grouping = Some(group_size);
+        self
+    }
+
+    pub fn with_notation(mut self, notation: FloatNotation) -> Self {
+        self.style.notation = notation;
+        self
+    }
+
+    pub fn with_min(mut self, min: impl Into<NumberType>) -> Self {
+        self.bounds.min = Some(min.into());
+        self
+    }
+
+    pub fn with_max(mut self, max: impl Into<NumberType>) -> Self {
+        self.bounds.max = Some(max.into());
+        self
+    }
+
+    /// Inclusive range.
+    pub fn with_range(self, min: impl Into<NumberType>, max: impl Into<NumberType>) -> Self {
+        self.with_min(min).with_max(max)
+    }
+
+    /// Valid values are `min + n * step`, or multiples of `step` without a `min`.
+    pub fn with_step(mut self, step: impl Into<NumberType>) -> Self {
+        self.bounds.step = Some(step.into());
+        self
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.bounds.unit = Some(unit.into());
+        self
+    }
+
+    /// `range: 0–100, step 5 (%)`, with the bounds in the same style as the value.
+    pub fn describe_bounds(&self, syntax: &NumberSyntax) -> Option<DocStr> {
+        let NumberBounds {
+            min,
+            max,
+            step,
+            unit,
+        } = &self.bounds;
+        let format = |number: &NumberType| number.format(self.style, syntax);
+
+        let range = match (min, max) {
+            (Some(min), Some(max)) => Some(format!("range: {}–{}", format(min), format(max))),
+            (Some(min), None) => Some(format!("min: {}", format(min))),
+            (None, Some(max)) => Some(format!("max: {}", format(max))),
+            (None, None) => None,
+        };
+        let range = match (range, step) {
+            (Some(range), Some(step)) => Some(format!("{range}, step {}", format(step))),
+            (None, Some(step)) => Some(format!("step: {}", format(step))),
+            (range, None) => range,
+        };
+
+        match (range, unit) {
+            (Some(range), Some(unit)) => Some(docstr!(format!("{range} ({unit})"))),
+            (Some(range), None) => Some(docstr!(range)),
+            (None, Some(unit)) => Some(docstr!(format!("unit: {unit}"))),
+            (None, None) => None,
+        }
+    }
+
+    /// Ways the value doesn't match its bounds.
+    pub fn check_bounds(&self) -> Vec<String> {
+        let NumberBounds { min, max, step, .. } = &self.bounds;
+        let mut problems = vec![];
+
+        if let Some(min) = min.filter(|min| self.ty < *min) {
+            problems.push(format!("{} is below the minimum of {min}", self.ty));
+        }
+
+        if let Some(max) = max.filter(|max| self.ty > *max) {
+            problems.push(format!("{} is above the maximum of {max}", self.ty));
+        }
+
+        if let Some(step) = step.filter(|step| step.as_f64() != 0.0) {
+            let origin = min.unwrap_or(NumberType::Integer(0));
+            let on_step = match (self.ty, origin, step) {
+                (
+                    NumberType::Integer(value),
+                    NumberType::Integer(origin),
+                    NumberType::Integer(step),
+                ) => value
+                    .checked_sub(origin)
+                    .and_then(|offset| offset.checked_rem(step))
+                    .is_none_or(|rem| rem == 0),
+                _ => {
+                    let steps = (self.ty.as_f64() - origin.as_f64()) / step.as_f64();
+                    (steps - steps.round()).abs() < 1e-9
+                }
+            };
+
+            if !on_step {
+                problems.push(format!("{} is not on a step of {step}", self.ty));
+            }
+        }
+
+        problems
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NumberBounds {
+    pub min: Option<NumberType>,
+    pub max: Option<NumberType>,
+    pub step: Option<NumberType>,
+    pub unit: Option<String>,
 }
 
-#[derive(Display)]
+#[derive(Debug, Clone, Copy)]
 pub enum NumberType {
-    Integer(i64),
+    Integer(i128),
+    /// Integers above `i128::MAX`.
+    Unsigned(u128),
     Float(f64),
 }
 
 impl NumberType {
     pub fn integer(int: impl IntoIntegerType) -> Self {
-        Self::Integer(int.into())
+        match int.to_i128() {
+            Some(int) => Self::Integer(int),
+            None => Self::Unsigned(
+                int.to_u128()
+                    .expect("Primitive integers fit in either i128 or u128"),
+            ),
+        }
     }
 
     pub fn float(float: impl IntoFloatType) -> Self {
         Self::Float(float.into())
     }
+
+    /// Formats the number as a literal of a format with the given syntax, falling back to the
+    /// closest supported style.
+    pub fn format(&self, style: NumberStyle, syntax: &NumberSyntax) -> String {
+        match *self {
+            Self::Integer(int) => format_integer(int < 0, int.unsigned_abs(), style, syntax),
+            Self::Unsigned(int) => format_integer(false, int, style, syntax),
+            Self::Float(float) => format_float(float, style, syntax),
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match *self {
+            Self::Integer(int) => int as f64,
+            Self::Unsigned(int) => int as f64,
+            Self::Float(float) => float,
+        }
+    }
+
+    /// Whether the number is within the range of the format's integers, floats always are.
+    pub fn fits(&self, syntax: &NumberSyntax) -> bool {
+        match *self {
+            Self::Integer(int) => {
+                int >= syntax.min_integer && (int < 0 || int.unsigned_abs() <= syntax.max_integer)
+            }
+            Self::Unsigned(int) => int <= syntax.max_integer,
+            Self::Float(_) => true,
+        }
+    }
+}
+
+impl PartialEq for NumberType {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for NumberType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (*self, *other) {
+            (Self::Integer(a), Self::Integer(b)) => a.partial_cmp(&b),
+            (Self::Unsigned(a), Self::Unsigned(b)) => a.partial_cmp(&b),
+            // Unsigned values are always above `i128::MAX`
+            (Self::Integer(_), Self::Unsigned(_)) => Some(Ordering::Less),
+            (Self::Unsigned(_), Self::Integer(_)) => Some(Ordering::Greater),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+}
+
+macro_rules! impl_from_primitive {
+    ($constructor:ident, $($ty:ty),+) => {
+        $(
+            impl From<$ty> for NumberType {
+                fn from(value: $ty) -> Self {
+                    Self::$constructor(value)
+                }
+            }
+        )+
+    };
+}
+
+impl_from_primitive!(integer, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_from_primitive!(float, f32, f64);
+
+impl Display for NumberType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(int) => write!(f, "{int}"),
+            Self::Unsigned(int) => write!(f, "{int}"),
+            Self::Float(float) => write!(f, "{float:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NumberStyle {
+    /// Only applies to non-negative integers.
+    pub radix: Radix,
+    /// Separate every this many digits with the format's digit separator, if it has one.
+    pub digit_grouping: Option<usize>,
+    pub notation: FloatNotation,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hexadecimal,
+}
+
+impl Radix {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Binary => "0b",
+            Self::Octal => "0o",
+            Self::Decimal => "",
+            Self::Hexadecimal => "0x",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FloatNotation {
+    /// Decimal, switching to scientific for very large or small magnitudes.
+    #[default]
+    Auto,
+    /// `1500.0`
+    Decimal,
+    /// `1.5e3`
+    Scientific,
+}
+
+/// Number literals a format supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberSyntax {
+    /// Prefixed radixes for non-negative integers, decimal is always supported.
+    pub radixes: &'static [Radix],
+    pub digit_separator: Option<char>,
+    pub min_integer: i128,
+    pub max_integer: u128,
+    pub inf: &'static str,
+    pub nan: &'static str,
+}
+
+fn format_integer(
+    negative: bool,
+    magnitude: u128,
+    style: NumberStyle,
+    syntax: &NumberSyntax,
+) -> String {
+    let radix = match !negative && syntax.radixes.contains(&style.radix) {
+        true => style.radix,
+        false => Radix::Decimal,
+    };
+    let digits = match radix {
+        Radix::Binary => format!("{magnitude:b}"),
+        Radix::Octal => format!("{magnitude:o}"),
+        Radix::Decimal => magnitude.to_string(),
+        Radix::Hexadecimal => format!("{magnitude:X}"),
+    };
+    let sign = if negative { "-" } else { "" };
+
+    format!(
+        "{sign}{}{}",
+        radix.prefix(),
+        group_digits(&digits, style, syntax)
+    )
+}
+
+fn format_float(float: f64, style: NumberStyle, syntax: &NumberSyntax) -> String {
+    if float.is_nan() {
+        return syntax.nan.to_string();
+    }
+
+    if float.is_infinite() {
+        let sign = if float < 0.0 { "-" } else { "" };
+        return format!("{sign}{}", syntax.inf);
+    }
+
+    let magnitude = float.abs();
+    let scientific = match style.notation {
+        FloatNotation::Auto => magnitude != 0.0 && !(1e-5..1e16).contains(&magnitude),
+        FloatNotation::Decimal => false,
+        FloatNotation::Scientific => true,
+    };
+
+    let sign = if float.is_sign_negative() { "-" } else { "" };
+    let (mantissa, exponent) = match scientific {
+        true => {
+            let formatted = format!("{magnitude:e}");
+            let (mantissa, exponent) = formatted.split_once('e').expect("`{:e}` has an exponent");
+
+            (mantissa.to_string(), Some(exponent.to_string()))
+        }
+        false => (magnitude.to_string(), None),
+    };
+
+    // Without a decimal point the float would be read back as an integer
+    let (int, fraction) = mantissa.split_once('.').unwrap_or((&mantissa, "0"));
+    let exponent = exponent
+        .map(|exponent| format!("e{exponent}"))
+        .unwrap_or_default();
+
+    format!(
+        "{sign}{}.{fraction}{exponent}",
+        group_digits(int, style, syntax)
+    )
+}
+
+fn group_digits(digits: &str, style: NumberStyle, syntax: &NumberSyntax) -> String {
+    let (Some(size), Some(separator)) = (style.digit_grouping, syntax.digit_separator) else {
+        return digits.to_string();
+    };
+
+    if size == 0 {
+        return digits.to_string();
+    }
+
+    let chars = digits.chars().collect::<Vec<_>>();
+    let first = match chars.len() % size {
+        0 => size,
+        rest => rest,
+    };
+    let mut res = chars[..first.min(chars.len())].iter().collect::<String>();
+
+    for group in chars[first.min(chars.len())..].chunks(size) {
+        res.push(separator);
+        res.extend(group);
+    }
+
+    res
 }
 
-pub trait IntoIntegerType: num::Integer + Into<i64> {}
+pub trait IntoIntegerType: num::PrimInt {}
 pub trait IntoFloatType: num::Float + Into<f64> {}
 
-impl<I> IntoIntegerType for I where I: num::Integer + Into<i64> {}
+impl<I> IntoIntegerType for I where I: num::PrimInt {}
 impl<F> IntoFloatType for F where F: num::Float + Into<f64> {}
 
 #[macro_export]
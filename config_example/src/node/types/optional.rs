@@ -0,0 +1,32 @@
+use super::NodeType;
+
+/// Value that may be left unset, `example` shows what it looks like either way.
+#[derive(Clone)]
+pub struct OptionalNode {
+    pub example: Box<NodeType>,
+    pub is_set: bool,
+}
+
+impl OptionalNode {
+    pub fn set(value: impl Into<NodeType>) -> Self {
+        Self {
+            example: Box::new(value.into()),
+            is_set: true,
+        }
+    }
+
+    /// Unset by default, formats render their null or comment the example out.
+    pub fn unset(example: impl Into<NodeType>) -> Self {
+        Self {
+            example: Box::new(example.into()),
+            is_set: false,
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! optional {
+    ($example:expr) => {
+        $crate::node::types::optional::OptionalNode::unset($example)
+    };
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use crate::node::key::Key;
+
+/// Filesystem path, with `~`, `$VAR`, `${VAR}` and `%VAR%` placeholders kept as is unless a
+/// value to expand them to is given.
+#[derive(Clone)]
+pub struct PathNode {
+    pub path: String,
+    pub name: Option<Key>,
+    /// Prefer strings without escapes, e.g. TOML literal strings.
+    pub literal: bool,
+    pub expansions: Vec<(String, String)>,
+    /// The same path on Windows, mentioned in a comment.
+    pub windows: Option<String>,
+}
+
+impl PathNode {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_string_lossy().into_owned(),
+            name: None,
+            literal: false,
+            expansions: vec![],
+            windows: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_literal(mut self, literal: bool) -> Self {
+        self.literal = literal;
+        self
+    }
+
+    /// Expands the `variable` placeholder to `value`, `~` for the home directory.
+    pub fn with_expansion(mut self, variable: impl Into<String>, value: impl Into<String>) -> Self {
+        self.expansions.push((variable.into(), value.into()));
+        self
+    }
+
+    pub fn with_windows(mut self, path: impl AsRef<Path>) -> Self {
+        self.windows = Some(path.as_ref().to_string_lossy().into_owned());
+        self
+    }
+
+    /// Path with the known placeholders expanded.
+    pub fn expanded(&self) -> String {
+        expand(&self.path, &self.expansions)
+    }
+
+    pub fn expanded_windows(&self) -> Option<String> {
+        self.windows
+            .as_ref()
+            .map(|path| expand(path, &self.expansions))
+    }
+}
+
+fn expand(path: &str, expansions: &[(String, String)]) -> String {
+    let value = |variable: &str| {
+        expansions
+            .iter()
+            .find(|(name, _)| name == variable)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let mut res = String::with_capacity(path.len());
+    let mut rest = path;
+
+    if let (Some(after), Some(home)) = (rest.strip_prefix('~'), value("~")) {
+        if after.is_empty() || after.starts_with(['/', '\\']) {
+            res.push_str(home);
+            rest = after;
+        }
+    }
+
+    while let Some(start) = rest.find(['$', '%']) {
+        res.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+
+        let (variable, len) = match placeholder.as_bytes()[0] {
+            b'$' if placeholder[1..].starts_with('{') => match placeholder.find('}') {
+                Some(end) => (&placeholder[2..end], end + 1),
+                None => ("", 1),
+            },
+            b'$' => {
+                let end = placeholder[1..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .map_or(placeholder.len(), |end| end + 1);
+
+                (&placeholder[1..end], end)
+            }
+            _ => match placeholder[1..].find('%') {
+                Some(end) => (&placeholder[1..end + 1], end + 2),
+                None => ("", 1),
+            },
+        };
+
+        match value(variable).filter(|_| !variable.is_empty()) {
+            Some(value) => res.push_str(value),
+            None => res.push_str(&placeholder[..len]),
+        }
+
+        rest = &placeholder[len..];
+    }
+
+    res.push_str(rest);
+    res
+}
+
+#[macro_export]
+macro_rules! path {
+    ($path:expr) => {
+        $crate::node::types::path::PathNode::new($path)
+    };
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+use crate::node::key::Key;
+
+use super::NodeType;
+
+/// Value meant to be filled in later, e.g. by a templating tool or per environment, see
+/// [`Variables`].
+#[derive(Clone)]
+pub struct PlaceholderNode {
+    pub variable: String,
+    pub name: Option<Key>,
+    pub syntax: PlaceholderSyntax,
+}
+
+impl PlaceholderNode {
+    pub fn new(variable: impl Into<String>) -> Self {
+        Self {
+            variable: variable.into(),
+            name: None,
+            syntax: Default::default(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_syntax(mut self, syntax: PlaceholderSyntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    pub fn text(&self) -> String {
+        match self.syntax {
+            PlaceholderSyntax::Template => format!("{{{{ {} }}}}", self.variable),
+            PlaceholderSyntax::Env => format!("${{{}}}", self.variable),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderSyntax {
+    /// `{{ variable }}`
+    #[default]
+    Template,
+    /// `${VARIABLE}`
+    Env,
+}
+
+/// Values placeholders are substituted with, unbound ones are kept.
+#[derive(Default, Clone)]
+pub struct Variables(BTreeMap<String, NodeType>);
+
+impl Variables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `value` is usually unnamed, it takes the name of the placeholder.
+    pub fn with(mut self, variable: impl Into<String>, value: impl Into<NodeType>) -> Self {
+        self.0.insert(variable.into(), value.into());
+        self
+    }
+
+    /// Values of `other` take precedence.
+    pub fn merge(mut self, other: Variables) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    pub fn get(&self, variable: &str) -> Option<&NodeType> {
+        self.0.get(variable)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[macro_export]
+macro_rules! placeholder {
+    ($variable:expr) => {
+        $crate::node::types::placeholder::PlaceholderNode::new($variable)
+    };
+}
@@ -0,0 +1,18 @@
+use crate::{node::key::Key, util::DocStr};
+
+/// Value written with units, e.g. `"1h 30m"` or `"512MiB"`, or as an integer amount of one unit.
+pub trait Quantity {
+    fn name(&self) -> Option<&Key>;
+
+    /// Value as written, formatters quote it if it has units.
+    fn text(&self) -> String;
+
+    /// Integer amount of the unit the value is written as, `None` if it's written with units.
+    fn amount(&self) -> Option<u128>;
+
+    /// Comment explaining how the value is written, since there is no standard syntax.
+    fn describe_syntax(&self) -> DocStr;
+
+    /// Why the integer amount the value is written as loses part of it, if it does.
+    fn truncation(&self) -> Option<String>;
+}
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use crate::util::DocStr;
+
+/// Verbatim text per format, for what nodes can't express yet, e.g. YAML anchors.
+#[derive(Clone, Default)]
+pub struct RawNode {
+    /// Keyed by [`NodeFormatter::EXTENSION`](crate::node::formatter::NodeFormatter::EXTENSION).
+    pub texts: HashMap<String, DocStr>,
+}
+
+impl RawNode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_format(mut self, extension: impl Into<String>, text: impl Into<DocStr>) -> Self {
+        self.texts.insert(extension.into(), text.into());
+        self
+    }
+
+    pub fn text(&self, extension: &str) -> Option<&DocStr> {
+        self.texts.get(extension)
+    }
+}
+
+#[macro_export]
+macro_rules! raw {
+    ($($extension:expr => $text:expr),* $(,)?) => {
+        $crate::node::types::raw::RawNode::new()$(.with_format($extension, $text))*
+    };
+}
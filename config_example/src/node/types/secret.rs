@@ -0,0 +1,85 @@
+use crate::{docstr, node::key::Key, util::DocStr};
+
+/// Credential that is only ever shown as a placeholder, it holds no value to leak.
+#[derive(Clone)]
+pub struct SecretNode {
+    pub name: Option<Key>,
+    pub placeholder: SecretPlaceholder,
+    /// Where to obtain the secret, e.g. `Create a token at https://example.com/tokens`.
+    pub source: Option<DocStr>,
+}
+
+impl SecretNode {
+    pub fn new(placeholder: SecretPlaceholder) -> Self {
+        Self {
+            name: None,
+            placeholder,
+            source: None,
+        }
+    }
+
+    /// `<your-api-key>` for an `api_key` name.
+    pub fn named(name: impl Into<Key>) -> Self {
+        let name = name.into();
+        let hint = name
+            .segments()
+            .last()
+            .map(|segment| segment.replace(['_', ' ', '.'], "-").to_lowercase())
+            .unwrap_or_default();
+
+        Self::new(SecretPlaceholder::Hint(format!("your-{hint}"))).with_name(name)
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: SecretPlaceholder) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<DocStr>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn describe(&self) -> DocStr {
+        let usage = match &self.placeholder {
+            SecretPlaceholder::Hint(_) => "Secret, don't commit the real value".to_string(),
+            SecretPlaceholder::EnvVar(variable) => {
+                format!("Secret, set the {variable} environment variable")
+            }
+        };
+
+        match &self.source {
+            Some(source) => docstr!(usage).merge(source.clone()),
+            None => docstr!(usage),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretPlaceholder {
+    /// `<hint>`
+    Hint(String),
+    /// `${VARIABLE}`
+    EnvVar(String),
+}
+
+impl SecretPlaceholder {
+    pub fn text(&self) -> String {
+        match self {
+            Self::Hint(hint) => format!("<{hint}>"),
+            Self::EnvVar(variable) => format!("${{{variable}}}"),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! secret {
+    ($name:expr) => {
+        $crate::node::types::secret::SecretNode::named($name)
+    };
+}
@@ -0,0 +1,29 @@
+use crate::node::key::Key;
+
+/// Free-form text, quoted as the format requires.
+#[derive(Clone)]
+pub struct StringNode {
+    pub value: String,
+    pub name: Option<Key>,
+}
+
+impl StringNode {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            name: None,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+#[macro_export]
+macro_rules! string {
+    ($value:expr) => {
+        $crate::node::types::string::StringNode::new($value)
+    };
+}
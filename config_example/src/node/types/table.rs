@@ -0,0 +1,29 @@
+use crate::node::{key::Key, Node};
+
+/// Nodes nested under a key, e.g. dotted keys in TOML or a nested mapping in YAML.
+#[derive(Clone)]
+pub struct TableNode {
+    pub name: Key,
+    pub nodes: Vec<Node>,
+}
+
+impl TableNode {
+    pub fn new(name: impl Into<Key>, nodes: impl IntoIterator<Item = Node>) -> Self {
+        Self {
+            name: name.into(),
+            nodes: nodes.into_iter().collect(),
+        }
+    }
+
+    pub fn add_node(mut self, node: Node) -> Self {
+        self.nodes.push(node);
+        self
+    }
+}
+
+#[macro_export]
+macro_rules! table {
+    ($name:expr, [$($node:expr),* $(,)?]) => {
+        $crate::node::types::table::TableNode::new($name, [$($node),*])
+    };
+}
@@ -0,0 +1,169 @@
+use crate::{
+    node::{key::Key, Node},
+    util::DocStr,
+};
+
+use super::{
+    choice::{ChoiceNode, ChoiceVariant},
+    table::TableNode,
+};
+
+/// Serde-style tagged enum with one selected variant, the others are shown as alternatives.
+#[derive(Clone)]
+pub struct TaggedNode {
+    pub name: Option<Key>,
+    pub tagging: Tagging,
+    pub variants: Vec<TaggedVariant>,
+    /// Index into `variants`.
+    pub selected: usize,
+}
+
+impl TaggedNode {
+    /// Selects the first variant.
+    pub fn new(tagging: Tagging, variants: impl IntoIterator<Item = TaggedVariant>) -> Self {
+        Self {
+            name: None,
+            tagging,
+            variants: variants.into_iter().collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<Key>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Selects the variant with the given tag, keeps the selection if there is none.
+    pub fn with_selected(mut self, tag: &str) -> Self {
+        if let Some(index) = self.variants.iter().position(|variant| variant.tag == tag) {
+            self.selected = index;
+        }
+        self
+    }
+
+    /// Selected variant first, then the alternatives in order, each as the nodes serde would read.
+    pub fn into_variants(self) -> impl Iterator<Item = (bool, Option<DocStr>, Vec<Node>)> {
+        let Self {
+            name,
+            tagging,
+            mut variants,
+            selected,
+        } = self;
+
+        if selected < variants.len() {
+            let selected = variants.remove(selected);
+            variants.insert(0, selected);
+        }
+
+        variants
+            .into_iter()
+            .enumerate()
+            .map(move |(index, variant)| {
+                let doc = variant.doc.clone();
+
+                (
+                    index == 0,
+                    doc,
+                    tagging.variant_nodes(name.clone(), variant),
+                )
+            })
+    }
+}
+
+/// Enum representations, see <https://serde.rs/enum-representations.html>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Tagging {
+    /// `name.variant.field`, or just `name = "variant"` for variants without fields.
+    External,
+    /// `name.tag = "variant"` next to the fields.
+    Internal { tag: String },
+    /// `name.tag = "variant"` with the fields under `name.content`.
+    Adjacent { tag: String, content: String },
+    /// Only the fields.
+    Untagged,
+}
+
+impl Tagging {
+    /// Key the fields of the variant are nested under, relative to the node's name.
+    pub fn fields_key(&self, tag: &str) -> Option<Key> {
+        match self {
+            Self::External => Some(Key::new(tag)),
+            Self::Adjacent { content, .. } => Some(Key::new(content.as_str())),
+            Self::Internal { .. } | Self::Untagged => None,
+        }
+    }
+
+    fn variant_nodes(
+        &self,
+        name: Option<Key>,
+        TaggedVariant {
+            tag: value, fields, ..
+        }: TaggedVariant,
+    ) -> Vec<Node> {
+        let tag_node = |key: &str| Node::new(tag_value(value.clone()).with_name(key));
+
+        let nodes = match self {
+            Self::External if fields.is_empty() => {
+                let node = tag_value(value);
+
+                return vec![Node::new(match name {
+                    Some(name) => node.with_name(name),
+                    None => node,
+                })];
+            }
+            Self::External => vec![Node::new(TableNode::new(value, fields))],
+            Self::Internal { tag } => [tag_node(tag)].into_iter().chain(fields).collect(),
+            Self::Adjacent { tag, .. } if fields.is_empty() => vec![tag_node(tag)],
+            Self::Adjacent { tag, content } => {
+                vec![
+                    tag_node(tag),
+                    Node::new(TableNode::new(content.as_str(), fields)),
+                ]
+            }
+            Self::Untagged => fields,
+        };
+
+        match name {
+            Some(name) => vec![Node::new(TableNode::new(name, nodes))],
+            None => nodes,
+        }
+    }
+}
+
+/// The tag is a string, the other variants are listed as alternatives anyway.
+fn tag_value(value: String) -> ChoiceNode {
+    ChoiceNode::new(value, Vec::<ChoiceVariant>::new())
+}
+
+#[derive(Clone)]
+pub struct TaggedVariant {
+    pub tag: String,
+    pub doc: Option<DocStr>,
+    pub fields: Vec<Node>,
+}
+
+impl TaggedVariant {
+    pub fn new(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            doc: None,
+            fields: vec![],
+        }
+    }
+
+    pub fn with_doc(mut self, doc: impl Into<DocStr>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn with_fields(mut self, fields: impl IntoIterator<Item = Node>) -> Self {
+        self.fields.extend(fields);
+        self
+    }
+
+    pub fn add_node(mut self, node: Node) -> Self {
+        self.fields.push(node);
+        self
+    }
+}
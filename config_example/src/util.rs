@@ -1,5 +1,10 @@
+pub mod doc;
+pub mod markdown;
+
 use std::fmt::Display;
 
+use unicode_width::UnicodeWidthStr;
+
 #[macro_export]
 macro_rules! docstr {
     ($line:expr) => {
@@ -34,6 +39,25 @@ macro_rules! docstr_multi_iter {
     };
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Tabs,
+    Spaces(usize),
+}
+
+impl Indent {
+    pub fn unit(&self) -> String {
+        match self {
+            Self::Tabs => "\t".to_string(),
+            Self::Spaces(width) => " ".repeat(*width),
+        }
+    }
+
+    pub fn repeat(&self, depth: usize) -> String {
+        self.unit().repeat(depth)
+    }
+}
+
 #[derive(Clone)]
 pub enum DocStr {
     Line(String),
@@ -46,7 +70,7 @@ impl DocStr {
         let maybe_lines = line.lines().collect::<Vec<_>>();
 
         match maybe_lines.len() {
-            1 => Self::Line(line),
+            0 | 1 => Self::Line(line),
             _ => Self::multiline(maybe_lines),
         }
     }
@@ -77,10 +101,41 @@ impl DocStr {
         }
     }
 
-    pub fn tabbed(self, tabs: usize) -> Self {
-        match tabs {
+    pub fn tabbed(self, depth: usize, indent: Indent) -> Self {
+        match depth {
             0 => self,
-            _ => self.map(|s| format!("{}{s}", "\t".repeat(tabs))),
+            _ => {
+                let prefix = indent.repeat(depth);
+                self.map(|s| format!("{prefix}{s}"))
+            }
+        }
+    }
+
+    /// Breaks lines wider than `width` at spaces, keeping their indentation on continuation lines.
+    /// Words (including URLs and inline code spans) are never split, so they may overflow.
+    pub fn wrap(self, width: usize) -> Self {
+        let lines = self.into_lines();
+
+        match lines.iter().all(|line| str_width(line) <= width) {
+            true => docstr_multi!(lines),
+            false => docstr_multi_iter!(lines
+                .into_iter()
+                .flat_map(|line| wrap_line(line, width.max(1)))),
+        }
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        match self {
+            Self::Line(line) => std::slice::from_ref(line).iter(),
+            Self::MultiLine(lines) => lines.iter(),
+        }
+        .map(String::as_str)
+    }
+
+    pub fn is_blank(&self) -> bool {
+        match self {
+            Self::Line(line) => line.trim().is_empty(),
+            Self::MultiLine(lines) => lines.iter().all(|line| line.trim().is_empty()),
         }
     }
 
@@ -99,83 +154,51 @@ impl DocStr {
             lines
         };
 
-        Self::multiline(merged_lines)
+        Self::MultiLine(merged_lines)
     }
 
+    /// Lays `right` out as a block to the right of `self`, see [`DocStr::attach_right_at`].
     pub fn attach_right(self, right: impl Into<DocStr>) -> Self {
-        match (self, right.into()) {
-            (Self::Line(line), Self::Line(rline)) => docstr!(format!("{line} {rline}")),
-            (Self::Line(line), Self::MultiLine(mut rlines)) => match rlines.len() {
-                0 => docstr!(line),
-                1 => docstr!(line).attach_right(rlines.remove(0)),
-                _ => {
-                    let line_len = line.len();
-                    let fline = docstr!(line).attach_right(rlines.remove(0));
-
-                    fline.merge(docstr_multi_iter!(rlines.into_iter().flat_map(|rline| {
-                        (docstr!(" ".repeat(line_len)).attach_right(rline)).into_lines()
-                    })))
-                }
-            },
-            (Self::MultiLine(mut lines), Self::Line(rline)) => match lines.len() {
-                0 => rline.into(),
-                1 => docstr!(lines.remove(0)).attach_right(rline),
-                _ => (docstr!(lines.remove(0)).attach_right(rline)).merge(docstr_multi!(lines)),
-            },
-            (Self::MultiLine(mut lines), Self::MultiLine(mut rlines)) => {
-                let lines_len = lines.len();
-                let rlines_len = rlines.len();
-
-                match (lines_len, rlines_len) {
-                    (0, 0) => docstr_empty!(),
-                    (0, _) => docstr_multi!(rlines),
-                    (_, 0) => docstr_multi!(lines),
-                    _ => match lines_len == rlines_len {
-                        true => lines
-                            .into_iter()
-                            .zip(rlines)
-                            .map(|(line, rline)| docstr!(line).attach_right(rline))
-                            .reduce(Self::merge)
-                            .unwrap_or(docstr_empty!()),
-                        false => match lines_len > rlines_len {
-                            true => {
-                                let attachable = lines.drain(0..rlines_len);
-
-                                let attached = attachable
-                                    .into_iter()
-                                    .zip(rlines)
-                                    .map(|(line, rline)| docstr!(line).attach_right(rline))
-                                    .reduce(Self::merge)
-                                    .unwrap();
-
-                                lines.into_iter().fold(attached, |attached, other_line| {
-                                    attached.merge(other_line)
-                                })
-                            }
-                            false => {
-                                let max_line_len =
-                                    lines.iter().map(|line| line.len()).max().unwrap();
-
-                                let rattachable = rlines.drain(0..rlines_len);
-
-                                let rattached = rattachable
-                                    .into_iter()
-                                    .zip(lines)
-                                    .map(|(rline, line)| docstr!(line).attach_right(rline))
-                                    .reduce(Self::merge)
-                                    .unwrap();
-
-                                rlines.into_iter().fold(rattached, |rattached, rline| {
-                                    rattached.merge(
-                                        docstr!(" ".repeat(max_line_len)).attach_right(rline),
-                                    )
-                                })
-                            }
-                        },
-                    },
-                }
-            }
+        self.attach_right_at(right, 0)
+    }
+
+    /// Lays `right` out as a block to the right of `self`, separated by a one space gutter.
+    ///
+    /// The gutter starts at the display width of the widest left line, or at `min_column` if that
+    /// is larger. Row `i` is made of left line `i` padded to the gutter, the gutter and right line
+    /// `i`. Rows without a right line keep their left line unpadded, rows without a left line are
+    /// padded with spaces. If either side has no lines, the other one is returned as is.
+    pub fn attach_right_at(self, right: impl Into<DocStr>, min_column: usize) -> Self {
+        let lines = self.into_lines();
+        let rlines = right.into().into_lines();
+
+        if lines.is_empty() || rlines.is_empty() {
+            return docstr_multi!(match lines.is_empty() {
+                true => rlines,
+                false => lines,
+            });
         }
+
+        let column = lines
+            .iter()
+            .map(|line| str_width(line))
+            .max()
+            .unwrap_or_default()
+            .max(min_column);
+        let rows = lines.len().max(rlines.len());
+
+        let mut lines = lines.into_iter();
+        let mut rlines = rlines.into_iter();
+
+        docstr_multi_iter!((0..rows).map(|_| match (lines.next(), rlines.next()) {
+            (line, None) => line.unwrap_or_default(),
+            (line, Some(rline)) => {
+                let line = line.unwrap_or_default();
+                let padding = " ".repeat(column - str_width(&line));
+
+                format!("{line}{padding} {rline}")
+            }
+        }))
     }
 }
 
@@ -196,3 +219,204 @@ impl Display for DocStr {
         }
     }
 }
+
+fn wrap_line(line: String, width: usize) -> Vec<String> {
+    if str_width(&line) <= width {
+        return vec![line];
+    }
+
+    let content = line.trim_start();
+    let indent = &line[..line.len() - content.len()];
+    // Continuation lines of list items hang under the item text
+    let hanging_indent = match content.split_once(' ') {
+        Some((marker, _)) if is_list_marker(marker) => {
+            format!("{indent}{}", " ".repeat(str_width(marker) + 1))
+        }
+        _ => indent.to_string(),
+    };
+
+    let mut lines = vec![];
+    let mut current = indent.to_string();
+    let mut current_indent_len = indent.len();
+
+    for word in words(content) {
+        match current.len() == current_indent_len {
+            true => current.push_str(word),
+            false if str_width(&current) + 1 + str_width(word) > width => {
+                lines.push(std::mem::replace(
+                    &mut current,
+                    format!("{hanging_indent}{word}"),
+                ));
+                current_indent_len = hanging_indent.len();
+            }
+            false => {
+                current.push(' ');
+                current.push_str(word);
+            }
+        }
+    }
+
+    lines.push(current);
+    lines
+}
+
+fn is_list_marker(word: &str) -> bool {
+    matches!(word, "-" | "*" | "+")
+        || word
+            .strip_suffix(['.', ')'])
+            .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Splits on whitespace outside of inline code spans.
+fn words(str: &str) -> impl Iterator<Item = &str> {
+    let mut in_code = false;
+
+    str.split(move |c: char| {
+        if c == '`' {
+            in_code = !in_code;
+        }
+
+        c.is_whitespace() && !in_code
+    })
+    .filter(|word| !word.is_empty())
+}
+
+/// Display width of `str` in a terminal or editor, which is what all layout is measured in.
+pub fn str_width(str: &str) -> usize {
+    str.width()
+}
+
+pub fn double_quoted(str: &str) -> String {
+    let escaped = str
+        .chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            '\r' => "\\r".to_string(),
+            '\t' => "\\t".to_string(),
+            c if c.is_control() => format!("\\u{:04X}", c as u32),
+            c => c.to_string(),
+        })
+        .collect::<String>();
+
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+    use unicode_width::UnicodeWidthStr;
+
+    use super::DocStr;
+
+    fn block() -> impl Strategy<Value = Vec<String>> {
+        prop::collection::vec("[a-z =#日本語é\u{301}🦀]{0,8}", 0..5)
+    }
+
+    #[test]
+    fn attach_right_shapes() {
+        let attach = |left: &[&str], right: &[&str]| {
+            DocStr::multiline(left.iter().copied())
+                .attach_right(DocStr::multiline(right.iter().copied()))
+                .into_lines()
+        };
+
+        assert_eq!(attach(&["a = 1"], &["# c"]), ["a = 1 # c"]);
+        assert_eq!(
+            attach(&["a = 1"], &["# c", "# d"]),
+            ["a = 1 # c", "      # d"]
+        );
+        assert_eq!(
+            attach(&["a:", "  b: 1"], &["# c"]),
+            ["a:     # c", "  b: 1"]
+        );
+        assert_eq!(attach(&["ab", "c"], &["# 1", "# 2"]), ["ab # 1", "c  # 2"]);
+        assert_eq!(
+            attach(&["ab", "c"], &["# 1", "# 2", "# 3"]),
+            ["ab # 1", "c  # 2", "   # 3"]
+        );
+        assert_eq!(attach(&[], &["# c"]), ["# c"]);
+        assert_eq!(attach(&["a"], &[]), ["a"]);
+        assert_eq!(
+            attach(&["ключ = 1", "日本 = 2"], &["# 1", "# 2"]),
+            ["ключ = 1 # 1", "日本 = 2 # 2"]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn attach_right_layout(left in block(), right in block(), min_column in 0usize..20) {
+            let res = DocStr::multiline(left.clone())
+                .attach_right_at(DocStr::multiline(right.clone()), min_column)
+                .into_lines();
+
+            if left.is_empty() || right.is_empty() {
+                let expected = match left.is_empty() {
+                    true => &right,
+                    false => &left,
+                };
+
+                prop_assert_eq!(&res, expected);
+                return Ok(());
+            }
+
+            let column = left.iter().map(|line| line.width()).max().unwrap().max(min_column);
+            prop_assert_eq!(res.len(), left.len().max(right.len()));
+
+            for (index, row) in res.iter().enumerate() {
+                let line = left.get(index).map(String::as_str).unwrap_or_default();
+
+                match right.get(index) {
+                    Some(rline) => {
+                        prop_assert!(row.starts_with(line));
+                        prop_assert!(row.ends_with(rline.as_str()));
+
+                        let gutter = &row[line.len()..row.len() - rline.len()];
+                        prop_assert!(gutter.chars().all(|c| c == ' '));
+                        prop_assert_eq!(line.width() + gutter.len(), column + 1);
+                    }
+                    None => prop_assert_eq!(row, line),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wrap_keeps_paragraphs_and_unbreakable_words() {
+        let comment = DocStr::multiline([
+            "Timeout applied to every `request to the server` see https://example.com/docs/timeouts",
+            "",
+            "  Indented second paragraph that is long enough to wrap",
+        ]);
+
+        assert_eq!(
+            comment.wrap(30).into_lines(),
+            [
+                "Timeout applied to every",
+                "`request to the server` see",
+                "https://example.com/docs/timeouts",
+                "",
+                "  Indented second paragraph",
+                "  that is long enough to wrap",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_hangs_list_items() {
+        let comment = DocStr::line("  - first item of the list that wraps");
+
+        assert_eq!(
+            comment.wrap(20).into_lines(),
+            ["  - first item of", "    the list that", "    wraps"]
+        );
+    }
+
+    #[test]
+    fn wrap_measures_display_width() {
+        let comment = DocStr::line("日本語 日本語 日本語");
+
+        assert_eq!(comment.wrap(14).into_lines(), ["日本語 日本語", "日本語"]);
+    }
+}
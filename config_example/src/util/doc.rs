@@ -0,0 +1,448 @@
+use crate::{docstr_multi, docstr_multi_iter};
+
+use super::{str_width, DocStr, Indent};
+
+/// Layout tree formatters build node values from, rendered to a [`DocStr`] with a width budget.
+///
+/// Follows Wadler's "prettier printer": a [`Doc::group`] is laid out on one line when it fits in
+/// the remaining width, otherwise each of its [`Doc::line`]s and [`Doc::softline`]s breaks,
+/// continuing at the indentation of the enclosing [`Doc::nest`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Doc {
+    Nil,
+    Text(String),
+    /// Space when the enclosing group is flat, line break otherwise.
+    Line,
+    /// Nothing when the enclosing group is flat, line break otherwise.
+    SoftLine,
+    /// Always a line break, the enclosing groups are never flat.
+    HardLine,
+    /// Indents line breaks inside by the given amount of indentation levels.
+    Nest(usize, Box<Doc>),
+    Group(Box<Doc>),
+    /// First document when the enclosing group breaks, second one when it's flat.
+    IfBreak(Box<Doc>, Box<Doc>),
+    /// Starts every line inside with the text, before the indentation, e.g. to comment it out.
+    Prefix(String, Box<Doc>),
+    /// Pads the document with spaces to at least the given width, e.g. to align keys.
+    Padded(usize, Box<Doc>),
+    Concat(Vec<Doc>),
+}
+
+impl Doc {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    pub fn line() -> Self {
+        Self::Line
+    }
+
+    pub fn softline() -> Self {
+        Self::SoftLine
+    }
+
+    pub fn hardline() -> Self {
+        Self::HardLine
+    }
+
+    pub fn if_break(broken: impl Into<Doc>, flat: impl Into<Doc>) -> Self {
+        Self::IfBreak(Box::new(broken.into()), Box::new(flat.into()))
+    }
+
+    pub fn concat(docs: impl IntoIterator<Item = Doc>) -> Self {
+        Self::Concat(docs.into_iter().collect())
+    }
+
+    /// Joins `docs` with `separator`, e.g. `Doc::text(",").append(Doc::line())` for lists.
+    pub fn join(docs: impl IntoIterator<Item = Doc>, separator: Doc) -> Self {
+        let mut joined = vec![];
+
+        for (index, doc) in docs.into_iter().enumerate() {
+            if index > 0 {
+                joined.push(separator.clone());
+            }
+
+            joined.push(doc);
+        }
+
+        Self::Concat(joined)
+    }
+
+    pub fn append(self, other: impl Into<Doc>) -> Self {
+        match self {
+            Self::Concat(mut docs) => {
+                docs.push(other.into());
+                Self::Concat(docs)
+            }
+            doc => Self::Concat(vec![doc, other.into()]),
+        }
+    }
+
+    pub fn nest(self, levels: usize) -> Self {
+        Self::Nest(levels, Box::new(self))
+    }
+
+    pub fn group(self) -> Self {
+        Self::Group(Box::new(self))
+    }
+
+    /// Expected at the start of a line, nothing is prefixed to [`Doc::Nil`].
+    pub fn prefixed(self, prefix: impl Into<String>) -> Self {
+        match self {
+            Self::Nil => Self::Nil,
+            doc => Self::Prefix(prefix.into(), Box::new(doc)),
+        }
+    }
+
+    pub fn padded(self, width: usize) -> Self {
+        Self::Padded(width, Box::new(self))
+    }
+
+    /// Sets the width of the first [`Doc::Padded`], in both layouts of a [`Doc::IfBreak`].
+    /// Returns whether there was one.
+    pub fn set_padding(&mut self, width: usize) -> bool {
+        match self {
+            Self::Padded(padding, _) => {
+                *padding = width;
+                true
+            }
+            Self::IfBreak(broken, flat) => broken.set_padding(width) | flat.set_padding(width),
+            Self::Nest(_, doc) | Self::Group(doc) | Self::Prefix(_, doc) => doc.set_padding(width),
+            Self::Concat(docs) => docs.iter_mut().any(|doc| doc.set_padding(width)),
+            Self::Nil | Self::Text(_) | Self::Line | Self::SoftLine | Self::HardLine => false,
+        }
+    }
+
+    /// Whether the document renders to blank lines only.
+    pub fn is_blank(&self) -> bool {
+        match self {
+            Self::Nil | Self::Line | Self::SoftLine | Self::HardLine => true,
+            Self::Text(text) => text.trim().is_empty(),
+            Self::Nest(_, doc) | Self::Group(doc) | Self::Padded(_, doc) => doc.is_blank(),
+            Self::IfBreak(broken, flat) => broken.is_blank() && flat.is_blank(),
+            Self::Prefix(_, _) => false,
+            Self::Concat(docs) => docs.iter().all(Self::is_blank),
+        }
+    }
+
+    /// Lays the document out, breaking groups that don't fit in `width` (never, if `None`).
+    pub fn render(&self, width: Option<usize>, indent: Indent) -> DocStr {
+        if *self == Self::Nil {
+            return docstr_multi!(Vec::<String>::new());
+        }
+
+        // Lines along with the length of their prefix
+        let mut lines = vec![(String::new(), 0)];
+        let mut prefixes = vec![String::new()];
+        let mut stack = vec![Command::Doc(Item {
+            level: 0,
+            mode: Mode::Break,
+            prefix: 0,
+            doc: self,
+        })];
+
+        while let Some(command) = stack.pop() {
+            let (line, prefix_len) = lines.last_mut().unwrap();
+            let item = match command {
+                Command::Doc(item) => item,
+                Command::PadTo(column) => {
+                    let padding = column.saturating_sub(str_width(line));
+                    line.push_str(&" ".repeat(padding));
+                    continue;
+                }
+            };
+            let nested = |doc| Command::Doc(Item { doc, ..item });
+
+            match item.doc {
+                Self::Nil => {}
+                Self::Text(text) => line.push_str(text),
+                Self::Line if item.mode == Mode::Flat => line.push(' '),
+                Self::SoftLine if item.mode == Mode::Flat => {}
+                Self::Line | Self::SoftLine | Self::HardLine => {
+                    let prefix = &prefixes[item.prefix];
+                    lines.push((
+                        format!("{prefix}{}", indent.repeat(item.level)),
+                        prefix.len(),
+                    ));
+                }
+                Self::Nest(levels, doc) => stack.push(Command::Doc(Item {
+                    level: item.level + levels,
+                    doc,
+                    ..item
+                })),
+                Self::Group(doc) => {
+                    let column = str_width(line);
+                    let mode = match item.mode == Mode::Flat || fits(width, column, doc, &stack) {
+                        true => Mode::Flat,
+                        false => Mode::Break,
+                    };
+
+                    stack.push(Command::Doc(Item { mode, doc, ..item }));
+                }
+                Self::IfBreak(broken, flat) => stack.push(nested(match item.mode {
+                    Mode::Flat => flat,
+                    Mode::Break => broken,
+                })),
+                Self::Prefix(text, doc) => {
+                    line.push_str(text);
+                    *prefix_len = line.len();
+                    prefixes.push(format!("{}{text}", prefixes[item.prefix]));
+                    stack.push(Command::Doc(Item {
+                        prefix: prefixes.len() - 1,
+                        doc,
+                        ..item
+                    }));
+                }
+                Self::Padded(width, doc) => {
+                    stack.push(Command::PadTo(str_width(line) + width));
+                    stack.push(nested(doc));
+                }
+                Self::Concat(docs) => stack.extend(docs.iter().rev().map(nested)),
+            }
+        }
+
+        // Indentation of lines left empty, keeping only their prefix
+        docstr_multi_iter!(lines.into_iter().map(|(mut line, prefix_len)| {
+            if line[prefix_len..].trim().is_empty() {
+                line.truncate(prefix_len);
+                line.truncate(line.trim_end().len());
+            }
+
+            line
+        }))
+    }
+}
+
+impl From<DocStr> for Doc {
+    fn from(value: DocStr) -> Self {
+        let lines = value.into_lines();
+
+        match lines.is_empty() {
+            true => Self::Nil,
+            false => Self::join(lines.into_iter().map(Self::Text), Self::HardLine),
+        }
+    }
+}
+
+impl From<String> for Doc {
+    fn from(value: String) -> Self {
+        Self::from(DocStr::line(value))
+    }
+}
+
+impl From<&str> for Doc {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+#[derive(Clone, Copy)]
+struct Item<'a> {
+    /// Indentation levels of line breaks.
+    level: usize,
+    mode: Mode,
+    /// Index of the text lines start with.
+    prefix: usize,
+    doc: &'a Doc,
+}
+
+enum Command<'a> {
+    Doc(Item<'a>),
+    /// Pads the line with spaces to the display column, the end of a [`Doc::Padded`].
+    PadTo(usize),
+}
+
+/// Whether `doc` laid out flat, followed by the rest of the line, fits from `column` on.
+fn fits(width: Option<usize>, mut column: usize, doc: &Doc, rest: &[Command]) -> bool {
+    let Some(width) = width else {
+        return !contains_hardline(doc);
+    };
+
+    let mut stack = vec![Command::Doc(Item {
+        level: 0,
+        mode: Mode::Flat,
+        prefix: 0,
+        doc,
+    })];
+    let mut rest = rest.iter().rev();
+
+    while column <= width {
+        let Some(command) = stack.pop().or_else(|| {
+            rest.next().map(|command| match command {
+                Command::Doc(item) => Command::Doc(*item),
+                Command::PadTo(column) => Command::PadTo(*column),
+            })
+        }) else {
+            return true;
+        };
+        let item = match command {
+            Command::Doc(item) => item,
+            Command::PadTo(to) => {
+                column = column.max(to);
+                continue;
+            }
+        };
+        let nested = |doc| Command::Doc(Item { doc, ..item });
+
+        match item.doc {
+            Doc::Nil => {}
+            Doc::Text(text) => column += str_width(text),
+            Doc::Line if item.mode == Mode::Flat => column += 1,
+            Doc::SoftLine if item.mode == Mode::Flat => {}
+            Doc::HardLine if item.mode == Mode::Flat => return false,
+            Doc::Line | Doc::SoftLine | Doc::HardLine => return true,
+            Doc::Nest(_, doc) | Doc::Group(doc) => stack.push(nested(doc)),
+            Doc::IfBreak(broken, flat) => stack.push(nested(match item.mode {
+                Mode::Flat => flat,
+                Mode::Break => broken,
+            })),
+            Doc::Prefix(text, doc) => {
+                column += str_width(text);
+                stack.push(nested(doc));
+            }
+            Doc::Padded(width, doc) => {
+                stack.push(Command::PadTo(column + width));
+                stack.push(nested(doc));
+            }
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(nested)),
+        }
+    }
+
+    false
+}
+
+/// Whether `doc` can't be laid out flat.
+fn contains_hardline(doc: &Doc) -> bool {
+    match doc {
+        Doc::HardLine => true,
+        Doc::Nest(_, doc) | Doc::Group(doc) | Doc::Prefix(_, doc) | Doc::Padded(_, doc) => {
+            contains_hardline(doc)
+        }
+        Doc::IfBreak(_, flat) => contains_hardline(flat),
+        Doc::Concat(docs) => docs.iter().any(contains_hardline),
+        Doc::Nil | Doc::Text(_) | Doc::Line | Doc::SoftLine => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::util::Indent;
+
+    use super::Doc;
+
+    fn array(items: &[&str]) -> Doc {
+        Doc::text("ports = [")
+            .append(
+                Doc::softline()
+                    .append(Doc::join(
+                        items.iter().map(|item| Doc::text(*item)),
+                        Doc::text(",").append(Doc::line()),
+                    ))
+                    .nest(1),
+            )
+            .append(Doc::softline())
+            .append("]")
+            .group()
+    }
+
+    #[test]
+    fn groups_break_only_when_too_wide() {
+        let doc = array(&["8080", "8081", "8082"]);
+
+        assert_eq!(
+            doc.render(Some(80), Indent::Spaces(4)).to_string(),
+            "ports = [8080, 8081, 8082]"
+        );
+        assert_eq!(
+            doc.render(Some(20), Indent::Spaces(4)).to_string(),
+            "ports = [\n    8080,\n    8081,\n    8082\n]"
+        );
+        assert_eq!(
+            doc.render(None, Indent::Spaces(4)).to_string(),
+            "ports = [8080, 8081, 8082]"
+        );
+    }
+
+    #[test]
+    fn hardlines_break_enclosing_groups() {
+        let doc = Doc::text("a:")
+            .append(Doc::hardline().append("b: 1").nest(1))
+            .group();
+
+        assert_eq!(
+            doc.render(None, Indent::Spaces(2)).to_string(),
+            "a:\n  b: 1"
+        );
+    }
+
+    #[test]
+    fn prefixes_start_every_line() {
+        let doc = Doc::text("a:")
+            .append(
+                Doc::hardline()
+                    .append("b: 1")
+                    .append(Doc::hardline())
+                    .append(Doc::hardline())
+                    .append("c: 2")
+                    .nest(1),
+            )
+            .prefixed("# ");
+
+        assert_eq!(
+            doc.render(None, Indent::Spaces(2)).into_lines(),
+            ["# a:", "#   b: 1", "#", "#   c: 2"]
+        );
+    }
+
+    #[test]
+    fn padding_counts_towards_fitting() {
+        let mut doc = Doc::text("key")
+            .padded(0)
+            .append(" = ")
+            .append(array(&["1", "2"]));
+
+        assert_eq!(
+            doc.render(Some(20), Indent::Spaces(2)).to_string(),
+            "key = ports = [1, 2]"
+        );
+        assert!(doc.set_padding(6));
+        assert_eq!(
+            doc.render(Some(20), Indent::Spaces(2)).to_string(),
+            "key    = ports = [\n  1,\n  2\n]"
+        );
+    }
+
+    #[test]
+    fn if_break_follows_the_group() {
+        let list = |width| {
+            Doc::text("[")
+                .append(Doc::softline().append("1").nest(1))
+                .append(Doc::if_break(",", Doc::Nil))
+                .append(Doc::softline())
+                .append("]")
+                .group()
+                .render(Some(width), Indent::Spaces(2))
+                .to_string()
+        };
+
+        assert_eq!(list(10), "[1]");
+        assert_eq!(list(2), "[\n  1,\n]");
+    }
+
+    #[test]
+    fn rest_of_line_counts_towards_fitting() {
+        let doc = array(&["1", "2"]).append(" # a long trailing text");
+
+        assert_eq!(
+            doc.render(Some(20), Indent::Tabs).to_string(),
+            "ports = [\n\t1,\n\t2\n] # a long trailing text"
+        );
+    }
+}
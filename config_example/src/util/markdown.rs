@@ -0,0 +1,401 @@
+use std::collections::HashSet;
+
+use crate::{docstr_multi, node::formatter::options::LinkStyle};
+
+use super::{wrap_line, DocStr};
+
+/// Renders rustdoc markdown as plain text suitable for config comments.
+///
+/// Fenced code blocks are indented instead of fenced, headings lose their `#`s, links keep only
+/// their text (with URLs moved to footnotes for [`LinkStyle::Footnotes`]), and list indentation,
+/// inline code and everything else is kept as is. Brackets are only read as links when they have
+/// a target, a defined label or a backticked intra-doc path, so `values[0]` stays as is.
+pub fn render_plain(doc: DocStr, links: LinkStyle) -> DocStr {
+    render(doc, links, None)
+}
+
+/// [`render_plain`], with everything but code blocks wrapped to `width`.
+pub fn render_wrapped(doc: DocStr, links: LinkStyle, width: usize) -> DocStr {
+    render(doc, links, Some(width))
+}
+
+fn render(doc: DocStr, links: LinkStyle, width: Option<usize>) -> DocStr {
+    let wrap = |line: String| match width {
+        Some(width) => wrap_line(line, width.max(1)),
+        None => vec![line],
+    };
+    let doc = doc.into_lines();
+    let labels = labels(&doc);
+    let mut inline = Inline {
+        links,
+        labels: &labels,
+        notes: vec![],
+        last_note: 0,
+    };
+    let mut lines = vec![];
+    let mut definitions = vec![];
+    let mut in_code_block = false;
+
+    for line in doc {
+        let trimmed = line.trim_start();
+
+        if is_fence(trimmed) {
+            in_code_block = !in_code_block;
+            continue;
+        }
+
+        if in_code_block {
+            lines.push(format!("    {line}"));
+            continue;
+        }
+
+        if let Some((_, definition)) = link_definition(trimmed) {
+            if links == LinkStyle::Footnotes {
+                definitions.push(definition.to_string());
+            }
+            continue;
+        }
+
+        let line = match heading(trimmed) {
+            Some(heading) => heading.to_string(),
+            None => line,
+        };
+
+        lines.extend(wrap(inline.render(&line)));
+    }
+
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+
+    if !inline.notes.is_empty() || !definitions.is_empty() {
+        lines.push(String::new());
+        lines.extend(inline.notes.into_iter().flat_map(wrap));
+        lines.extend(definitions.into_iter().flat_map(wrap));
+    }
+
+    docstr_multi!(lines)
+}
+
+fn is_fence(line: &str) -> bool {
+    line.starts_with("```") || line.starts_with("~~~")
+}
+
+/// Labels of the reference definitions outside of code blocks, lowercased.
+fn labels(lines: &[String]) -> HashSet<String> {
+    let mut in_code_block = false;
+
+    lines
+        .iter()
+        .map(|line| line.trim_start())
+        .filter(|line| {
+            if is_fence(line) {
+                in_code_block = !in_code_block;
+            }
+
+            !in_code_block
+        })
+        .filter_map(|line| link_definition(line).map(|(label, _)| label.to_lowercase()))
+        .collect()
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+
+    match (1..=6).contains(&level) && text.starts_with(' ') {
+        true => Some(text.trim()),
+        false => None,
+    }
+}
+
+/// `[label]: target` reference definition lines, along with the label.
+fn link_definition(line: &str) -> Option<(&str, &str)> {
+    let (label, target) = line.strip_prefix('[')?.split_once("]:")?;
+
+    match label.is_empty() || target.trim().is_empty() {
+        true => None,
+        false => Some((label, line)),
+    }
+}
+
+/// Intra-doc links like [`Foo`] or [`crate::foo()`].
+fn is_intra_doc_path(text: &str) -> bool {
+    let Some(path) = text
+        .strip_prefix('`')
+        .and_then(|text| text.strip_suffix('`'))
+    else {
+        return false;
+    };
+
+    path.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && path
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_:@!()<>".contains(c))
+}
+
+struct Inline<'a> {
+    links: LinkStyle,
+    labels: &'a HashSet<String>,
+    /// Numbered footnotes of inline link URLs.
+    notes: Vec<String>,
+    last_note: usize,
+}
+
+impl Inline<'_> {
+    fn render(&mut self, line: &str) -> String {
+        let chars = line.chars().collect::<Vec<_>>();
+        let mut res = String::with_capacity(line.len());
+        let mut index = 0;
+
+        while index < chars.len() {
+            match chars[index] {
+                '`' => {
+                    let end = find(&chars, index + 1, '`').unwrap_or(chars.len() - 1);
+                    res.extend(&chars[index..=end]);
+                    index = end + 1;
+                }
+                '*' => match strong(&chars, index) {
+                    Some(end) => {
+                        let text = chars[index + 2..end].iter().collect::<String>();
+                        res.push_str(&self.render(&text));
+                        index = end + 2;
+                    }
+                    None => {
+                        res.push('*');
+                        index += 1;
+                    }
+                },
+                '[' => match link(&chars, index).filter(|link| self.is_link(link)) {
+                    Some(Link { text, target, end }) => {
+                        res.push_str(&self.render(&text));
+
+                        match (self.links, target) {
+                            (LinkStyle::Footnotes, LinkTarget::Url(url)) if is_url(&url) => {
+                                let note = self.next_note();
+                                self.notes.push(format!("[{note}]: {url}"));
+                                res.push_str(&format!("[{note}]"));
+                            }
+                            (LinkStyle::Footnotes, LinkTarget::Label(label))
+                                if self.labels.contains(&label.to_lowercase()) =>
+                            {
+                                res.push_str(&format!("[{label}]"));
+                            }
+                            _ => {}
+                        }
+
+                        index = end;
+                    }
+                    None => {
+                        res.push('[');
+                        index += 1;
+                    }
+                },
+                c => {
+                    res.push(c);
+                    index += 1;
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Bracketed text without a target is only a link if it refers to something.
+    fn is_link(&self, Link { text, target, .. }: &Link) -> bool {
+        match target {
+            LinkTarget::Url(_) => true,
+            LinkTarget::Label(label) => {
+                self.labels.contains(&label.to_lowercase()) || is_intra_doc_path(label)
+            }
+            LinkTarget::None => {
+                self.labels.contains(&text.to_lowercase()) || is_intra_doc_path(text)
+            }
+        }
+    }
+
+    /// Next footnote number that isn't taken by a reference definition.
+    fn next_note(&mut self) -> usize {
+        self.last_note = (self.last_note + 1..)
+            .find(|number| !self.labels.contains(&number.to_string()))
+            .expect("There are fewer labels than numbers");
+        self.last_note
+    }
+}
+
+struct Link {
+    text: String,
+    target: LinkTarget,
+    end: usize,
+}
+
+enum LinkTarget {
+    Url(String),
+    Label(String),
+    None,
+}
+
+/// Parses `[text](target)`, `[text][label]` and intra-doc `[text]` links starting at `start`.
+fn link(chars: &[char], start: usize) -> Option<Link> {
+    let text_end = matching(chars, start, '[', ']')?;
+    let text = chars[start + 1..text_end].iter().collect::<String>();
+
+    match chars.get(text_end + 1) {
+        Some('(') => {
+            let target_end = matching(chars, text_end + 1, '(', ')')?;
+
+            Some(Link {
+                text,
+                target: LinkTarget::Url(chars[text_end + 2..target_end].iter().collect()),
+                end: target_end + 1,
+            })
+        }
+        Some('[') => {
+            let label_end = matching(chars, text_end + 1, '[', ']')?;
+
+            Some(Link {
+                text,
+                target: LinkTarget::Label(chars[text_end + 2..label_end].iter().collect()),
+                end: label_end + 1,
+            })
+        }
+        _ => Some(Link {
+            text,
+            target: LinkTarget::None,
+            end: text_end + 1,
+        }),
+    }
+}
+
+fn matching(chars: &[char], start: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+
+    for (index, c) in chars.iter().enumerate().skip(start) {
+        match *c {
+            c if c == open => depth += 1,
+            c if c == close => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// End of a `**strong**` span starting at `start`, its markers need to hug the text.
+fn strong(chars: &[char], start: usize) -> Option<usize> {
+    let text_start = start + 2;
+
+    if chars.get(start + 1) != Some(&'*') || chars.get(text_start)?.is_whitespace() {
+        return None;
+    }
+
+    (text_start + 1..chars.len().saturating_sub(1)).find(|index| {
+        chars[*index] == '*' && chars[index + 1] == '*' && !chars[index - 1].is_whitespace()
+    })
+}
+
+fn find(chars: &[char], start: usize, c: char) -> Option<usize> {
+    chars[start..]
+        .iter()
+        .position(|other| *other == c)
+        .map(|offset| start + offset)
+}
+
+fn is_url(target: &str) -> bool {
+    target.contains("://") || target.starts_with("mailto:")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{docstr_multi, node::formatter::options::LinkStyle};
+
+    use super::render_plain;
+
+    #[test]
+    fn renders_rustdoc_markdown() {
+        let doc = docstr_multi!(
+            "# Storage",
+            "Where [`Blob`]s are kept, see [the docs](https://example.com/storage).",
+            "",
+            "- **fs**: local [`Path`](std::path::Path)",
+            "  on disk",
+            "",
+            "```toml",
+            "path = \"/var/lib\"",
+            "```",
+            "Keep `[not, a, link]` as is, more in [guide][g].",
+            "",
+            "[g]: https://example.com/guide"
+        );
+
+        assert_eq!(
+            render_plain(doc.clone(), LinkStyle::Strip).into_lines(),
+            [
+                "Storage",
+                "Where `Blob`s are kept, see the docs.",
+                "",
+                "- fs: local `Path`",
+                "  on disk",
+                "",
+                "    path = \"/var/lib\"",
+                "Keep `[not, a, link]` as is, more in guide.",
+            ]
+        );
+        assert_eq!(
+            render_plain(doc, LinkStyle::Footnotes).into_lines()[1..],
+            [
+                "Where `Blob`s are kept, see the docs[1].",
+                "",
+                "- fs: local `Path`",
+                "  on disk",
+                "",
+                "    path = \"/var/lib\"",
+                "Keep `[not, a, link]` as is, more in guide[g].",
+                "",
+                "[1]: https://example.com/storage",
+                "[g]: https://example.com/guide",
+            ]
+        );
+    }
+
+    #[test]
+    fn brackets_without_targets_stay() {
+        let doc = docstr_multi!(
+            "Use values[0], default [1, 2], see [Defaults] and [`Config::port`].",
+            "Computes 2 ** 3, **not** `**this**`."
+        );
+
+        assert_eq!(
+            render_plain(doc, LinkStyle::Strip).into_lines(),
+            [
+                "Use values[0], default [1, 2], see [Defaults] and `Config::port`.",
+                "Computes 2 ** 3, not `**this**`.",
+            ]
+        );
+    }
+
+    #[test]
+    fn footnotes_skip_defined_labels() {
+        let doc = docstr_multi!(
+            "[1]: https://example.com/first",
+            "See [a](https://a.example), [b](https://b.example) and [first][1]."
+        );
+
+        assert_eq!(
+            render_plain(doc, LinkStyle::Footnotes).into_lines(),
+            [
+                "See a[2], b[3] and first[1].",
+                "",
+                "[2]: https://a.example",
+                "[3]: https://b.example",
+                "[1]: https://example.com/first",
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+mod schema;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro_crate::FoundCrate;
@@ -8,17 +10,29 @@ use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
-    token, Expr, Ident, Token,
+    token, DeriveInput, Expr, Ident, Token,
 };
 
+#[proc_macro_error]
+#[proc_macro_derive(ChoiceSchema, attributes(serde))]
+pub fn derive_choice_schema(input: TokenStream) -> TokenStream {
+    schema::derive_choice_schema(parse_macro_input!(input as DeriveInput)).into()
+}
+
 #[proc_macro_error]
 #[proc_macro]
 pub fn node(input: TokenStream) -> TokenStream {
     let crate_name = crate_name();
 
-    let NodeMacro { ty, tabs, comments } = parse_macro_input!(input);
+    let NodeMacro {
+        ty,
+        tabs,
+        commented,
+        comments,
+    } = parse_macro_input!(input);
 
     let tabs = tabs.map(|tabs| quote!(.with_tabs(#tabs)));
+    let commented = commented.map(|commented| quote!(.with_commented(#commented)));
     let comment_call = |comment| {
         let tokens = match comment {
             NodeComment::Line(line) => line.into_token_stream(),
@@ -41,7 +55,7 @@ pub fn node(input: TokenStream) -> TokenStream {
     });
 
     quote! {
-        #crate_name::node::Node::new(#ty) #tabs #comments
+        #crate_name::node::Node::new(#ty) #tabs #commented #comments
     }
     .into()
 }
@@ -49,21 +63,23 @@ pub fn node(input: TokenStream) -> TokenStream {
 struct NodeMacro {
     ty: Expr,
     tabs: Option<Expr>,
+    commented: Option<Expr>,
     comments: Option<NodeComments>,
 }
 
 impl NodeMacro {
-    const OPTIONS: [&'static str; 2] = ["tabs", "comments"];
+    const OPTIONS: [&'static str; 3] = ["tabs", "commented", "comments"];
 }
 
 impl Parse for NodeMacro {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let ty = input.parse()?;
 
-        let (tabs, comments) = match input.peek(Token![,]) {
+        let (tabs, commented, comments) = match input.peek(Token![,]) {
             true => {
                 let _comma: token::Comma = input.parse()?;
                 let mut tabs = None;
+                let mut commented = None;
                 let mut comments = None;
 
                 while !input.is_empty() {
@@ -74,6 +90,10 @@ impl Parse for NodeMacro {
                             let _eq: Token![=] = input.parse()?;
                             tabs = Some(input.parse()?)
                         }
+                        "commented" => {
+                            let _eq: Token![=] = input.parse()?;
+                            commented = Some(input.parse()?)
+                        }
                         "comments" => {
                             let _eq: Token![=] = input.parse()?;
                             comments = Some(input.parse()?);
@@ -92,12 +112,17 @@ impl Parse for NodeMacro {
                     }
                 }
 
-                (tabs, comments)
+                (tabs, commented, comments)
             }
-            false => (None, None),
+            false => (None, None, None),
         };
 
-        Ok(Self { ty, tabs, comments })
+        Ok(Self {
+            ty,
+            tabs,
+            commented,
+            comments,
+        })
     }
 }
 
@@ -0,0 +1,243 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::{
+    meta::ParseNestedMeta, parenthesized, token, Attribute, Data, DeriveInput, Expr, ExprLit,
+    Fields, Lit, LitStr, Meta,
+};
+
+use crate::crate_name;
+
+/// `ChoiceSchema` for unit-variant enums: a choice node with every variant as an option.
+pub fn derive_choice_schema(input: DeriveInput) -> TokenStream {
+    let crate_name = crate_name();
+    let ident = &input.ident;
+
+    let Data::Enum(data) = &input.data else {
+        abort!(
+            ident,
+            "ChoiceSchema can only be derived for unit-variant enums"
+        );
+    };
+
+    let rename_all = SerdeOptions::parse(&input.attrs).rename_all.map(|rule| {
+        match RenameRule::from_str(&rule.value()) {
+            Some(rename_rule) => rename_rule,
+            None => abort!(rule, "Unknown rename_all rule '{}'", rule.value()),
+        }
+    });
+
+    let mut selected = None;
+    let variants = data
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let serde = SerdeOptions::parse(&variant.attrs);
+
+            // Can't be deserialized, so it's no possible value
+            if serde.skip {
+                return None;
+            }
+
+            if !matches!(variant.fields, Fields::Unit) {
+                abort!(
+                    variant,
+                    "ChoiceSchema can only be derived for unit-variant enums"
+                );
+            }
+
+            let value = match serde.rename {
+                Some(rename) => rename.value(),
+                None => match rename_all {
+                    Some(rule) => rule.apply(&variant.ident.to_string()),
+                    None => variant.ident.to_string(),
+                },
+            };
+
+            if selected.is_none() && is_default(&variant.attrs) {
+                selected = Some(value.clone());
+            }
+
+            let doc = doc_lines(&variant.attrs);
+            let with_doc = (!doc.is_empty())
+                .then(|| quote!(.with_doc(#crate_name::util::DocStr::multiline([#(#doc),*]))));
+
+            Some((
+                value.clone(),
+                quote!(#crate_name::node::types::choice::ChoiceVariant::new(#value) #with_doc),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let Some(selected) = selected.or_else(|| variants.first().map(|(value, _)| value.clone()))
+    else {
+        abort!(
+            ident,
+            "ChoiceSchema can't be derived for enums without variants"
+        );
+    };
+    let variants = variants.into_iter().map(|(_, variant)| variant);
+
+    quote! {
+        impl #crate_name::node::types::choice::ChoiceSchema for #ident {
+            fn choice() -> #crate_name::node::types::choice::ChoiceNode {
+                #crate_name::node::types::choice::ChoiceNode::new(#selected, [#(#variants),*])
+            }
+        }
+    }
+}
+
+fn is_default(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident("default"))
+}
+
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(name_value) if name_value.path.is_ident("doc") => {
+                match &name_value.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(doc), ..
+                    }) => Some(doc.value()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// The `#[serde(...)]` options that change how a variant is deserialized, others are ignored.
+#[derive(Default)]
+struct SerdeOptions {
+    rename: Option<LitStr>,
+    rename_all: Option<LitStr>,
+    /// `skip` or `skip_deserializing`
+    skip: bool,
+}
+
+impl SerdeOptions {
+    fn parse(attrs: &[Attribute]) -> Self {
+        let mut options = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = deserialize_name(&meta)?;
+                } else if meta.path.is_ident("rename_all") {
+                    options.rename_all = deserialize_name(&meta)?;
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_deserializing") {
+                    options.skip = true;
+                } else if meta.input.peek(token::Paren) {
+                    // e.g. `bound(deserialize = "...")`
+                    let content;
+                    parenthesized!(content in meta.input);
+                    content.parse::<TokenStream>()?;
+                } else if meta.input.peek(token::Eq) {
+                    // e.g. `default = "path"`
+                    meta.value()?.parse::<Expr>()?;
+                }
+
+                Ok(())
+            });
+
+            if let Err(err) = parsed {
+                abort!(err.span(), "Invalid serde attribute: {}", err);
+            }
+        }
+
+        options
+    }
+}
+
+/// `name = "..."`, or the `deserialize` half of `name(serialize = "...", deserialize = "...")`.
+fn deserialize_name(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if !meta.input.peek(token::Paren) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+
+    let mut name = None;
+
+    meta.parse_nested_meta(|half| {
+        let value = half.value()?.parse()?;
+
+        if half.path.is_ident("deserialize") {
+            name = Some(value);
+        }
+
+        Ok(())
+    })?;
+
+    Ok(name)
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Same conversion serde applies to variant names, which are expected in PascalCase.
+    fn apply(self, variant: &str) -> String {
+        let snake = || {
+            let mut snake = String::new();
+
+            for (index, c) in variant.char_indices() {
+                if c.is_uppercase() && index > 0 {
+                    snake.push('_');
+                }
+
+                snake.push(c.to_ascii_lowercase());
+            }
+
+            snake
+        };
+
+        match self {
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Pascal => variant.to_string(),
+            Self::Camel => {
+                let mut chars = variant.chars();
+
+                // ASCII only, like serde
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            }
+            Self::Snake => snake(),
+            Self::ScreamingSnake => snake().to_ascii_uppercase(),
+            Self::Kebab => snake().replace('_', "-"),
+            Self::ScreamingKebab => snake().replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+}